@@ -0,0 +1,196 @@
+//! Starting the app on login, registered the way each platform expects.
+
+use std::path::{Path, PathBuf};
+
+const APP_NAME: &str = "toggl-tracker";
+
+fn executable() -> Result<PathBuf, String> {
+    std::env::current_exe()
+        .map_err(|e| format!("Can't find the app executable: {e}"))
+}
+
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "macos")),
+    allow(dead_code)
+)]
+fn write_file(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Can't create {}: {e}", dir.display()))?;
+    }
+    std::fs::write(path, contents)
+        .map_err(|e| format!("Can't write {}: {e}", path.display()))
+}
+
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "macos")),
+    allow(dead_code)
+)]
+fn remove_file(path: &Path) -> Result<(), String> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Can't remove {}: {e}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Quote a path for the `Exec` key of a desktop entry.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn desktop_entry(exe: &Path) -> String {
+    let mut quoted = String::new();
+    for c in exe.to_string_lossy().chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    // The key is a string value too, so its own backslashes are escaped
+    // again, and `%` would start a field code.
+    let quoted = quoted.replace('\\', "\\\\").replace('%', "%%");
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Toggl Tracker\n\
+         Exec=\"{quoted}\"\n\
+         X-GNOME-Autostart-enabled=true\n"
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn autostart_file() -> Result<PathBuf, String> {
+    let dirs = directories_next::BaseDirs::new()
+        .ok_or("Can't find the home directory")?;
+    Ok(dirs
+        .config_dir()
+        .join("autostart")
+        .join(format!("{APP_NAME}.desktop")))
+}
+
+#[cfg(target_os = "macos")]
+const LAUNCH_AGENT: &str = "com.sterliakov.toggl-tracker";
+
+#[cfg(target_os = "macos")]
+fn autostart_file() -> Result<PathBuf, String> {
+    let dirs = directories_next::BaseDirs::new()
+        .ok_or("Can't find the home directory")?;
+    Ok(dirs
+        .home_dir()
+        .join("Library/LaunchAgents")
+        .join(format!("{LAUNCH_AGENT}.plist")))
+}
+
+/// Whether the app is registered to start on login, whatever the saved
+/// setting says.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub fn is_enabled() -> bool {
+    autostart_file().is_ok_and(|path| path.is_file())
+}
+
+#[cfg(target_os = "linux")]
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    let path = autostart_file()?;
+    if enabled {
+        write_file(&path, &desktop_entry(&executable()?))
+    } else {
+        remove_file(&path)
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    let path = autostart_file()?;
+    if !enabled {
+        return remove_file(&path);
+    }
+    let exe = executable()?.to_string_lossy().replace('&', "&amp;");
+    write_file(
+        &path,
+        &format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{LAUNCH_AGENT}</string>
+    <key>ProgramArguments</key>
+    <array><string>{exe}</string></array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#
+        ),
+    )
+}
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+#[cfg(target_os = "windows")]
+pub fn is_enabled() -> bool {
+    std::process::Command::new("reg")
+        .args(["query", RUN_KEY, "/v", APP_NAME])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    let mut command = std::process::Command::new("reg");
+    if enabled {
+        let exe = executable()?;
+        command.args(["add", RUN_KEY, "/v", APP_NAME, "/t", "REG_SZ", "/f"]);
+        command.arg("/d").arg(format!("\"{}\"", exe.display()));
+    } else {
+        command.args(["delete", RUN_KEY, "/v", APP_NAME, "/f"]);
+    }
+    let output = command
+        .output()
+        .map_err(|e| format!("Can't run reg.exe: {e}"))?;
+    // Deleting a missing value fails too, which is fine.
+    if output.status.success() || !enabled {
+        Ok(())
+    } else {
+        Err(format!(
+            "Can't register autostart: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "windows"
+)))]
+pub fn is_enabled() -> bool {
+    false
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "windows"
+)))]
+pub fn set_enabled(_enabled: bool) -> Result<(), String> {
+    Err("Starting on login is not supported on this platform.".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::desktop_entry;
+
+    #[test]
+    fn test_desktop_entry_quotes_exec() {
+        let entry = desktop_entry(Path::new("/opt/my apps/$toggl"));
+        assert!(
+            entry.contains("Exec=\"/opt/my apps/\\\\$toggl\"\n"),
+            "{entry}"
+        );
+        let entry = desktop_entry(Path::new(r"/opt/a\b/100%"));
+        assert!(entry.contains(r#"Exec="/opt/a\\\\b/100%%""#), "{entry}");
+    }
+}
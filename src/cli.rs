@@ -0,0 +1,275 @@
+use itertools::Itertools;
+use log::warn;
+use serde::Serialize;
+
+use crate::client::Client;
+use crate::import::{import_file, COLUMNS};
+use crate::related_info::ExtendedMe;
+use crate::time_entry::{is_already_stopped, TimeEntry};
+use crate::State;
+
+/// Flags shared by the subcommands.
+#[derive(Clone, Copy, Debug, Default)]
+struct Options {
+    /// Report what a mutating command would do without doing it.
+    dry_run: bool,
+    /// Print machine-readable output, errors included.
+    json: bool,
+}
+
+impl Options {
+    /// Print `value` as JSON or the text from `human`, returning the exit
+    /// code.
+    fn print<T: Serialize>(
+        &self,
+        value: &T,
+        human: impl FnOnce() -> String,
+    ) -> i32 {
+        if !self.json {
+            println!("{}", human());
+            return 0;
+        }
+        match serde_json::to_string_pretty(value) {
+            Ok(json) => {
+                println!("{json}");
+                0
+            }
+            Err(e) => {
+                self.error(&e.to_string());
+                1
+            }
+        }
+    }
+
+    fn error(&self, message: &str) {
+        if self.json {
+            eprintln!("{}", serde_json::json!({"error": message}));
+        } else {
+            eprintln!("{message}");
+        }
+    }
+}
+
+/// Output of `status`.
+#[derive(Serialize)]
+struct Status<'a> {
+    running: bool,
+    entry: Option<&'a TimeEntry>,
+}
+
+/// Exit code of `status` while a timer runs, distinct from failures.
+const RUNNING_EXIT_CODE: i32 = 3;
+
+/// Run a subcommand if `args` name one, returning the process exit code.
+/// `None` means there is nothing to do here and the GUI should start.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (flags, positional): (Vec<_>, Vec<_>) =
+        args.iter().partition(|a| a.starts_with("--"));
+    let command = positional.first()?;
+    let mut options = Options::default();
+    let mut unknown = None;
+    for flag in flags {
+        match flag.as_str() {
+            "--dry-run" => options.dry_run = true,
+            "--json" => options.json = true,
+            // Reported once all flags are read, `--json` may come later.
+            _ => unknown = unknown.or(Some(flag)),
+        }
+    }
+    if let Some(flag) = unknown {
+        options.error(&format!("Unknown option: {flag}"));
+        return Some(2);
+    }
+    match (command.as_str(), &positional[1..]) {
+        ("import-csv", [path]) => {
+            Some(async_std::task::block_on(import_csv(path, options)))
+        }
+        ("import-csv", _) => {
+            options.error(&format!(
+                "Usage: toggl-tracker import-csv [--dry-run] [--json] <file>\n\
+                 Columns: {COLUMNS}"
+            ));
+            Some(2)
+        }
+        ("start", words) => {
+            let text = words.iter().join(" ");
+            Some(async_std::task::block_on(start(&text, options)))
+        }
+        ("stop", []) => Some(async_std::task::block_on(stop(options))),
+        ("stop", _) => {
+            options.error("Usage: toggl-tracker stop");
+            Some(2)
+        }
+        ("status", []) => Some(async_std::task::block_on(status(options))),
+        ("status", _) => {
+            options.error("Usage: toggl-tracker status");
+            Some(2)
+        }
+        _ => None,
+    }
+}
+
+async fn load_state(options: Options) -> Option<Box<State>> {
+    match State::load().await {
+        Ok(state) => Some(state),
+        Err(e) => {
+            options.error(&format!(
+                "Not logged in ({e:?}), start the app to log in first."
+            ));
+            None
+        }
+    }
+}
+
+/// One line about `entry` for the terminal.
+fn describe(entry: &TimeEntry, state: &State) -> String {
+    let project = state
+        .projects
+        .iter()
+        .find(|p| Some(p.id) == entry.project_id)
+        .map(|p| p.name.as_str());
+    entry.details(project, &state.customization)
+}
+
+/// Start an entry from text like the new entry input takes.
+async fn start(text: &str, options: Options) -> i32 {
+    let Some(state) = load_state(options).await else {
+        return 1;
+    };
+    let client = Client::from_api_token(&state.api_token);
+    // Fresh projects and tags for the `@project` and `#tag` tokens.
+    let state = match ExtendedMe::load(&client).await {
+        Ok(me) => state.update_from_context(me),
+        Err(e) => {
+            warn!("Failed to refresh projects, using saved ones: {e}");
+            *state
+        }
+    };
+    let entry = match state.new_entry(text, true) {
+        Ok(entry) => entry,
+        Err(e) => {
+            options.error(&e);
+            return 1;
+        }
+    };
+    match entry.create(&client).await {
+        Ok(entry) => options
+            .print(&entry, || format!("Started: {}", describe(&entry, &state))),
+        Err(e) => {
+            options.error(&format!("Failed to start an entry: {e}"));
+            1
+        }
+    }
+}
+
+async fn stop(options: Options) -> i32 {
+    let Some(state) = load_state(options).await else {
+        return 1;
+    };
+    let client = Client::from_api_token(&state.api_token);
+    let entry = match TimeEntry::current(&client).await {
+        Ok(Some(entry)) => entry,
+        Ok(None) => {
+            options.error("No timer is running.");
+            return 1;
+        }
+        Err(e) => {
+            options.error(&format!("Failed to load the running entry: {e}"));
+            return 1;
+        }
+    };
+    match entry.stop_rounded(&client, &state.customization).await {
+        Ok(stopped) => options.print(&stopped, || {
+            format!("Stopped: {}", describe(&stopped, &state))
+        }),
+        Err(e) if is_already_stopped(&e) => {
+            options.error("No timer is running.");
+            1
+        }
+        Err(e) => {
+            options.error(&format!("Failed to stop the running entry: {e}"));
+            1
+        }
+    }
+}
+
+/// Print the running entry; the exit code tells whether there is one.
+async fn status(options: Options) -> i32 {
+    let Some(state) = load_state(options).await else {
+        return 1;
+    };
+    let client = Client::from_api_token(&state.api_token);
+    let entry = match TimeEntry::current(&client).await {
+        Ok(entry) => entry,
+        Err(e) => {
+            options.error(&format!("Failed to load the running entry: {e}"));
+            return 1;
+        }
+    };
+    let status = Status {
+        running: entry.is_some(),
+        entry: entry.as_ref(),
+    };
+    let printed = options.print(&status, || match &entry {
+        Some(entry) => format!("Running: {}", describe(entry, &state)),
+        None => "No timer is running.".to_string(),
+    });
+    match printed {
+        0 if status.running => RUNNING_EXIT_CODE,
+        code => code,
+    }
+}
+
+async fn import_csv(path: &str, options: Options) -> i32 {
+    let Some(state) = load_state(options).await else {
+        return 1;
+    };
+    let Some(workspace_id) = state.default_workspace else {
+        options.error("No workspace selected.");
+        return 1;
+    };
+    let client = Client::from_api_token(&state.api_token);
+    // Projects might have been added since the state was saved.
+    let projects = match ExtendedMe::load(&client).await {
+        Ok(me) => me.projects,
+        Err(e) => {
+            warn!("Failed to refresh projects, using saved ones: {e}");
+            state.projects
+        }
+    };
+    match import_file(path, &projects, workspace_id, &client, options.dry_run)
+        .await
+    {
+        Ok(report) => match options.print(&report, || report.to_string()) {
+            0 => i32::from(!report.errors.is_empty()),
+            code => code,
+        },
+        Err(e) => {
+            options.error(&e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::run;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_run_without_subcommand_starts_gui() {
+        assert_eq!(run(&args(&[])), None);
+        assert_eq!(run(&args(&["--json"])), None);
+    }
+
+    #[test]
+    fn test_run_rejects_bad_usage() {
+        assert_eq!(run(&args(&["import-csv"])), Some(2));
+        assert_eq!(run(&args(&["import-csv", "--force", "a.csv"])), Some(2));
+        assert_eq!(run(&args(&["stop", "now"])), Some(2));
+        assert_eq!(run(&args(&["status", "all"])), Some(2));
+    }
+}
@@ -1,4 +1,5 @@
-use iced::widget::button;
+use iced::widget::{button, checkbox, container};
+use iced_aw::menu;
 
 pub fn menu_button<T>(
     content: &str,
@@ -13,3 +14,19 @@ pub fn menu_button<T>(
         .on_press(message)
         .width(iced::Length::Fill)
 }
+
+pub fn toggle_item<'a, T: 'a + Clone>(
+    label: &'a str,
+    is_checked: bool,
+    on_toggle: impl Fn(bool) -> T + 'a,
+) -> menu::Item<'a, T, iced::Theme, iced::Renderer> {
+    menu::Item::new(
+        container(
+            checkbox(label, is_checked)
+                .on_toggle(on_toggle)
+                .size(14)
+                .text_size(14),
+        )
+        .padding([4, 4]),
+    )
+}
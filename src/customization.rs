@@ -1,10 +1,18 @@
-use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
-use iced::widget::{button, text};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
+#[cfg(feature = "gui")]
+use iced::widget::{button, column, container, text, text_input};
+#[cfg(feature = "gui")]
 use iced::Task as Command;
+#[cfg(feature = "gui")]
 use iced_aw::menu;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
-use crate::components::menu_button;
+use crate::client::{split_url_password, NetworkSettings};
+#[cfg(feature = "gui")]
+use crate::components::{menu_button, toggle_item};
+use crate::rules::Rule;
+use crate::time_entry::{format_duration, parse_elapsed, to_start_of_week};
 
 trait LocaleString {
     fn to_format_string(&self) -> String;
@@ -36,7 +44,7 @@ impl std::fmt::Display for DateFormat {
     }
 }
 impl DateFormat {
-    const VALUES: [Self; 2] = [Self::Dmy, Self::Mdy];
+    pub const VALUES: [Self; 2] = [Self::Dmy, Self::Mdy];
 }
 
 #[derive(
@@ -66,16 +74,254 @@ impl std::fmt::Display for TimeFormat {
 }
 
 impl TimeFormat {
-    const VALUES: [Self; 2] = [Self::H12, Self::H24];
+    pub const VALUES: [Self; 2] = [Self::H12, Self::H24];
+}
+
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, Default, Serialize, Deserialize,
+)]
+pub enum DurationFormat {
+    #[default]
+    Hms,
+    HoursMinutes,
+    Decimal,
+}
+impl std::fmt::Display for DurationFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            DurationFormat::Hms => "1:23:45",
+            DurationFormat::HoursMinutes => "1h 23m",
+            DurationFormat::Decimal => "1.40h",
+        };
+        f.write_str(repr)
+    }
+}
+
+impl DurationFormat {
+    pub const VALUES: [Self; 3] =
+        [Self::Hms, Self::HoursMinutes, Self::Decimal];
+}
+
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, Default, Serialize, Deserialize,
+)]
+pub enum NudgeStep {
+    One,
+    #[default]
+    Five,
+    Ten,
+    Fifteen,
+}
+impl std::fmt::Display for NudgeStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} min", self.minutes())
+    }
+}
+
+impl NudgeStep {
+    pub const VALUES: [Self; 4] =
+        [Self::One, Self::Five, Self::Ten, Self::Fifteen];
+
+    fn minutes(&self) -> i64 {
+        match self {
+            NudgeStep::One => 1,
+            NudgeStep::Five => 5,
+            NudgeStep::Ten => 10,
+            NudgeStep::Fifteen => 15,
+        }
+    }
+}
+
+/// Step to which entry durations are rounded.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, Default, Serialize, Deserialize,
+)]
+pub enum RoundingMode {
+    #[default]
+    None,
+    Five,
+    Six,
+    Fifteen,
+}
+impl std::fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.minutes() {
+            None => f.write_str("No rounding"),
+            Some(minutes) => write!(f, "{minutes} min"),
+        }
+    }
+}
+
+impl RoundingMode {
+    pub const VALUES: [Self; 4] =
+        [Self::None, Self::Five, Self::Six, Self::Fifteen];
+
+    fn minutes(&self) -> Option<i64> {
+        match self {
+            RoundingMode::None => None,
+            RoundingMode::Five => Some(5),
+            RoundingMode::Six => Some(6),
+            RoundingMode::Fifteen => Some(15),
+        }
+    }
+}
+
+/// Period covered by the total shown in the menu bar.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, Default, Serialize, Deserialize,
+)]
+pub enum TotalPeriod {
+    #[default]
+    Week,
+    LastSevenDays,
+    SinceDate,
+}
+impl std::fmt::Display for TotalPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            TotalPeriod::Week => "This week",
+            TotalPeriod::LastSevenDays => "Last 7 days",
+            TotalPeriod::SinceDate => "Since date",
+        };
+        f.write_str(repr)
+    }
+}
+
+impl TotalPeriod {
+    pub const VALUES: [Self; 3] =
+        [Self::Week, Self::LastSevenDays, Self::SinceDate];
+}
+
+/// What to do once the running entry is stopped.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, Default, Serialize, Deserialize,
+)]
+pub enum PostStopAction {
+    #[default]
+    Nothing,
+    FocusNewEntry,
+    EditEntry,
+}
+impl std::fmt::Display for PostStopAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            PostStopAction::Nothing => "Do nothing",
+            PostStopAction::FocusNewEntry => "Focus new entry input",
+            PostStopAction::EditEntry => "Edit the stopped entry",
+        };
+        f.write_str(repr)
+    }
+}
+
+impl PostStopAction {
+    pub const VALUES: [Self; 3] =
+        [Self::Nothing, Self::FocusNewEntry, Self::EditEntry];
+}
+
+/// Optional details shown in an entry row under the description.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EntryColumns {
+    pub project: bool,
+    pub client: bool,
+    pub tags: bool,
+    pub billable: bool,
+}
+
+impl Default for EntryColumns {
+    fn default() -> Self {
+        Self {
+            project: true,
+            client: false,
+            tags: false,
+            billable: false,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Customization {
     date_format: DateFormat,
     time_format: TimeFormat,
+    duration_format: DurationFormat,
+    nudge_step: NudgeStep,
+    rounding: RoundingMode,
+    /// Save rounded times instead of only showing rounded durations.
+    pub round_on_save: bool,
+    total_period: TotalPeriod,
+    /// Start date for [`TotalPeriod::SinceDate`], in the date format.
+    total_since: String,
+    /// Proxy URL to use instead of the one from the environment.
+    proxy: String,
+    /// Password of the proxy user; the app keeps it in the OS keyring, never
+    /// in the state file.
+    #[serde(skip)]
+    proxy_password: String,
+    /// Path to a PEM bundle of extra CA certificates to trust.
+    ca_certificate: String,
+    /// Rules filling in project and tags from the description.
+    pub rules: Vec<Rule>,
+    /// Comma-separated tags added to every new entry.
+    default_tags: String,
+    /// Ask before quitting while a timer is running.
+    pub confirm_quit: bool,
+    /// Minimize the window instead of quitting when it is closed.
+    pub minimize_on_close: bool,
+    /// Duplicate entries over a running timer without asking.
+    pub skip_replace_confirmation: bool,
+    /// Collapse consecutive entries with the same description and project.
+    pub group_similar_entries: bool,
+    /// Show the running duration without seconds, updated each minute.
+    pub calm_running_timer: bool,
+    pub columns: EntryColumns,
+    /// Re-fetch entries after a bulk edit and reload if they differ.
+    pub verify_edits: bool,
+    /// Keep the window above the other ones.
+    pub always_on_top: bool,
+    pub post_stop_action: PostStopAction,
+    /// Minimize the window right after launch.
+    pub start_minimized: bool,
+    /// Whether the app is registered to start on login.
+    pub start_on_login: bool,
+    /// Offer to discard idle time after this many minutes, 0 to never ask.
+    pub idle_minutes: u32,
 }
 
+/// Choices offered for [`Customization::idle_minutes`].
+#[cfg(feature = "gui")]
+const IDLE_MINUTES: [u32; 5] = [0, 5, 10, 15, 30];
+
+/// Optional file next to the state, overriding the saved customization.
+pub const CONFIG_FILE: &str = "config.json";
+
 impl Customization {
+    /// Apply the fields of a JSON object over these settings.
+    pub fn with_overrides(&self, json: &str) -> Result<Self, String> {
+        let overrides: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid {CONFIG_FILE}: {e}"))?;
+        let serde_json::Value::Object(overrides) = overrides else {
+            return Err(format!("{CONFIG_FILE} must contain a JSON object"));
+        };
+        let mut merged = serde_json::to_value(self)
+            .map_err(|e| format!("Can't serialize customization: {e}"))?;
+        for (key, value) in overrides {
+            let Some(field) = merged.get_mut(&key) else {
+                return Err(format!("Unknown field '{key}' in {CONFIG_FILE}"));
+            };
+            *field = value;
+            // Check field by field to point at the broken one.
+            serde_json::from_value::<Self>(merged.clone()).map_err(|e| {
+                format!("Invalid '{key}' in {CONFIG_FILE}: {e}")
+            })?;
+        }
+        let mut merged: Self = serde_json::from_value(merged)
+            .map_err(|e| format!("Invalid {CONFIG_FILE}: {e}"))?;
+        merged.proxy_password.clone_from(&self.proxy_password);
+        merged.take_proxy_url_password();
+        Ok(merged)
+    }
+
     fn datetime_format(&self) -> String {
         format!(
             "{} {}",
@@ -89,6 +335,164 @@ impl Customization {
             .to_string()
     }
 
+    pub fn format_time(&self, time: &DateTime<Local>) -> String {
+        time.format(&self.time_format.to_format_string())
+            .to_string()
+    }
+
+    pub fn format_duration(&self, duration: &Duration) -> String {
+        format_duration(duration, self.duration_format)
+    }
+
+    /// Duration of the running entry, `H:MM` in the calm mode.
+    pub fn format_running(&self, duration: &Duration) -> String {
+        if self.calm_running_timer {
+            let minutes = duration.num_minutes().max(0);
+            format!("{}:{:0>2}", minutes / 60, minutes % 60)
+        } else {
+            self.format_duration(duration)
+        }
+    }
+
+    /// Label and start of the period covered by the menu bar total.
+    ///
+    /// Falls back to the current week if the custom date doesn't parse.
+    pub fn total_period(
+        &self,
+        now: DateTime<Local>,
+    ) -> (String, DateTime<Local>) {
+        let week = ("Week total".to_string(), to_start_of_week(now));
+        match self.total_period {
+            TotalPeriod::Week => week,
+            TotalPeriod::LastSevenDays => {
+                ("Last 7 days".to_string(), now - Duration::days(7))
+            }
+            TotalPeriod::SinceDate => self
+                .parse_date(&self.total_since)
+                .ok()
+                .and_then(|d| {
+                    d.and_time(NaiveTime::MIN)
+                        .and_local_timezone(Local)
+                        .earliest()
+                })
+                .map_or(week, |start| {
+                    (format!("Since {}", self.total_since), start)
+                }),
+        }
+    }
+
+    pub fn network_settings(&self) -> NetworkSettings {
+        let non_empty = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        NetworkSettings {
+            proxy: non_empty(&self.proxy),
+            proxy_password: non_empty(&self.proxy_password),
+            ca_certificate: non_empty(&self.ca_certificate)
+                .or_else(|| {
+                    std::env::var(NetworkSettings::CA_CERTIFICATE_ENV).ok()
+                })
+                .map(PathBuf::from),
+        }
+    }
+
+    pub fn proxy_password(&self) -> &str {
+        &self.proxy_password
+    }
+
+    pub fn set_proxy_password(&mut self, password: String) {
+        self.proxy_password = password;
+    }
+
+    /// Move a password typed into the proxy URL out of it, so that it is not
+    /// saved with the URL. Returns whether there was one.
+    pub fn take_proxy_url_password(&mut self) -> bool {
+        let (proxy, password) = split_url_password(&self.proxy);
+        let Some(password) = password else {
+            return false;
+        };
+        self.proxy = proxy;
+        self.proxy_password = password;
+        true
+    }
+
+    /// Tags to attach to new entries, without blanks and duplicates.
+    pub fn default_tags(&self) -> Vec<String> {
+        split_tags(&self.default_tags)
+    }
+
+    /// Idle time after which to offer discarding it, if enabled.
+    pub fn idle_threshold(&self) -> Option<Duration> {
+        (self.idle_minutes > 0)
+            .then(|| Duration::minutes(self.idle_minutes.into()))
+    }
+
+    /// Amount by which the +/- buttons move start and stop times.
+    pub fn nudge_step(&self) -> Duration {
+        Duration::minutes(self.nudge_step.minutes())
+    }
+
+    /// Round `duration` to the nearest step, halves going up.
+    ///
+    /// Anything shorter than a step counts as one, so that a short entry
+    /// isn't billed (or saved) as empty.
+    pub fn round(&self, duration: &Duration) -> Duration {
+        let Some(minutes) = self.rounding.minutes() else {
+            return *duration;
+        };
+        let step = minutes * 60;
+        let seconds = duration.num_seconds().max(0);
+        if seconds == 0 {
+            return Duration::zero();
+        }
+        Duration::seconds(((seconds + step / 2) / step * step).max(step))
+    }
+
+    /// Stop time to save for an entry, rounded if asked to.
+    pub fn stop_to_save(
+        &self,
+        start: DateTime<Local>,
+        stop: DateTime<Local>,
+    ) -> DateTime<Local> {
+        if self.round_on_save && stop >= start {
+            start + self.round(&(stop - start))
+        } else {
+            stop
+        }
+    }
+
+    /// Total with the still ticking part shown apart, e.g.
+    /// "6:12:00 (+0:45:00 running)".
+    pub fn format_total(
+        &self,
+        committed: &Duration,
+        running: &Duration,
+    ) -> String {
+        if running.is_zero() {
+            self.format_duration(committed)
+        } else {
+            format!(
+                "{} (+{} running)",
+                self.format_duration(committed),
+                self.format_running(running)
+            )
+        }
+    }
+
+    /// Parse a time of day in the configured format, or as 24h `HH:MM`.
+    pub fn parse_time(&self, text: &str) -> Result<NaiveTime, String> {
+        let text = text.trim();
+        NaiveTime::parse_from_str(text, &self.time_format.to_format_string())
+            .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+            .map_err(|_| format!("Can't read '{text}' as a time"))
+    }
+
+    pub fn parse_date(&self, text: &str) -> Result<NaiveDate, String> {
+        NaiveDate::parse_from_str(text, &self.date_format.to_format_string())
+            .map_err(|e| e.to_string())
+    }
+
     pub fn format_datetime(
         &self,
         datetime: &Option<DateTime<Local>>,
@@ -108,6 +512,9 @@ impl Customization {
         if text.is_empty() {
             return Ok(None);
         }
+        if let Some(datetime) = parse_relative_datetime(text, Local::now()) {
+            return Ok(Some(datetime));
+        }
         let naive =
             NaiveDateTime::parse_from_str(text, &self.datetime_format())
                 .map_err(|e| e.to_string())?;
@@ -117,14 +524,72 @@ impl Customization {
     }
 }
 
+/// Shorthands accepted besides the configured format: `now`, an offset like
+/// `-15m` or `+1h 30m`, a time today like `09:30` or `2:15 pm`, and
+/// `today 14:00` or `yesterday 14:00`.
+pub fn parse_relative_datetime(
+    text: &str,
+    now: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    let text = text.trim().to_lowercase();
+    if text == "now" {
+        return Some(now);
+    }
+    if let Some(offset) = text.strip_prefix('-') {
+        return parse_elapsed(offset).ok().map(|offset| now - offset);
+    }
+    if let Some(offset) = text.strip_prefix('+') {
+        return parse_elapsed(offset).ok().map(|offset| now + offset);
+    }
+    let (day, time) = if let Some(time) = text.strip_prefix("yesterday") {
+        (now.date_naive().pred_opt()?, time)
+    } else if let Some(time) = text.strip_prefix("today") {
+        (now.date_naive(), time)
+    } else {
+        (now.date_naive(), text.as_str())
+    };
+    let time = time.trim();
+    let time = ["%H:%M", "%H:%M:%S", "%I:%M %p", "%I:%M%p"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(time, format).ok())?;
+    day.and_time(time).and_local_timezone(Local).earliest()
+}
+
 #[derive(Clone, Debug)]
 pub enum CustomizationMessage {
     SelectTimeFormat(TimeFormat),
     SelectDateFormat(DateFormat),
+    SelectDurationFormat(DurationFormat),
+    SelectNudgeStep(NudgeStep),
+    SelectRounding(RoundingMode),
+    SelectTotalPeriod(TotalPeriod),
+    SelectPostStopAction(PostStopAction),
+    SelectIdleMinutes(u32),
+    SetTotalSince(String),
+    SetDefaultTags(String),
+    SetProxy(String),
+    SetProxyPassword(String),
+    SetCaCertificate(String),
+    SetConfirmQuit(bool),
+    SetMinimizeOnClose(bool),
+    SetGroupSimilarEntries(bool),
+    SetCalmRunningTimer(bool),
+    SetRoundOnSave(bool),
+    SetColumns(EntryColumns),
+    SetVerifyEdits(bool),
+    SetAlwaysOnTop(bool),
+    SetStartMinimized(bool),
+    /// Ask to (un)register the app to start on login.
+    RequestStartOnLogin(bool),
+    /// Registration changed, remember it.
+    SetStartOnLogin(bool),
+    SetSkipReplaceConfirmation(bool),
+    EditRules,
     Discarded,
     Save,
 }
 
+#[cfg(feature = "gui")]
 impl Customization {
     pub fn update(
         &mut self,
@@ -139,9 +604,99 @@ impl Customization {
                 self.date_format = fmt;
                 Command::done(CustomizationMessage::Save)
             }
-            CustomizationMessage::Discarded | CustomizationMessage::Save => {
-                Command::none()
+            CustomizationMessage::SelectDurationFormat(fmt) => {
+                self.duration_format = fmt;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SelectNudgeStep(step) => {
+                self.nudge_step = step;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SelectRounding(rounding) => {
+                self.rounding = rounding;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SelectTotalPeriod(period) => {
+                self.total_period = period;
+                Command::done(CustomizationMessage::Save)
             }
+            CustomizationMessage::SelectIdleMinutes(minutes) => {
+                self.idle_minutes = minutes;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SelectPostStopAction(action) => {
+                self.post_stop_action = action;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetTotalSince(date) => {
+                self.total_since = date;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetDefaultTags(tags) => {
+                self.default_tags = tags;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetProxy(proxy) => {
+                self.proxy = proxy;
+                self.take_proxy_url_password();
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetProxyPassword(password) => {
+                self.proxy_password = password;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetCaCertificate(path) => {
+                self.ca_certificate = path;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetConfirmQuit(value) => {
+                self.confirm_quit = value;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetMinimizeOnClose(value) => {
+                self.minimize_on_close = value;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetGroupSimilarEntries(value) => {
+                self.group_similar_entries = value;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetCalmRunningTimer(value) => {
+                self.calm_running_timer = value;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetRoundOnSave(value) => {
+                self.round_on_save = value;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetAlwaysOnTop(value) => {
+                self.always_on_top = value;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetStartMinimized(value) => {
+                self.start_minimized = value;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetStartOnLogin(value) => {
+                self.start_on_login = value;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetVerifyEdits(value) => {
+                self.verify_edits = value;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetColumns(columns) => {
+                self.columns = columns;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::SetSkipReplaceConfirmation(value) => {
+                self.skip_replace_confirmation = value;
+                Command::done(CustomizationMessage::Save)
+            }
+            CustomizationMessage::Discarded
+            | CustomizationMessage::Save
+            | CustomizationMessage::RequestStartOnLogin(_)
+            | CustomizationMessage::EditRules => Command::none(),
         }
     }
 
@@ -170,9 +725,231 @@ impl Customization {
                     ),
                     self.date_format_menu(wrapper),
                 ),
+                menu::Item::with_menu(
+                    menu_button(
+                        "Duration format",
+                        wrapper(CustomizationMessage::Discarded),
+                    ),
+                    self.duration_format_menu(wrapper),
+                ),
+                menu::Item::with_menu(
+                    menu_button(
+                        "Adjustment step",
+                        wrapper(CustomizationMessage::Discarded),
+                    ),
+                    self.nudge_step_menu(wrapper),
+                ),
+                menu::Item::with_menu(
+                    menu_button(
+                        "Rounding",
+                        wrapper(CustomizationMessage::Discarded),
+                    ),
+                    self.rounding_menu(wrapper),
+                ),
+                menu::Item::with_menu(
+                    menu_button(
+                        "Total period",
+                        wrapper(CustomizationMessage::Discarded),
+                    ),
+                    self.total_period_menu(wrapper),
+                ),
+                menu::Item::with_menu(
+                    menu_button(
+                        "After stopping",
+                        wrapper(CustomizationMessage::Discarded),
+                    ),
+                    self.post_stop_menu(wrapper),
+                ),
+                menu::Item::with_menu(
+                    menu_button(
+                        "Idle prompt",
+                        wrapper(CustomizationMessage::Discarded),
+                    ),
+                    self.idle_menu(wrapper),
+                ),
+                menu::Item::with_menu(
+                    menu_button(
+                        "Entry details",
+                        wrapper(CustomizationMessage::Discarded),
+                    ),
+                    self.columns_menu(wrapper),
+                ),
+                menu::Item::new(menu_button(
+                    "Categorization rules",
+                    wrapper(CustomizationMessage::EditRules),
+                )),
+                menu::Item::new(
+                    container(
+                        column![
+                            text("Default tags").size(14),
+                            text_input("tag, another tag", &self.default_tags)
+                                .on_input(|v| wrapper(
+                                    CustomizationMessage::SetDefaultTags(v)
+                                ))
+                                .size(14),
+                        ]
+                        .spacing(4),
+                    )
+                    .padding([4, 4]),
+                ),
+                menu::Item::new(
+                    container(
+                        column![
+                            text("Proxy").size(14),
+                            text_input("http://user@host:port", &self.proxy)
+                                .on_input(|v| wrapper(
+                                    CustomizationMessage::SetProxy(v)
+                                ))
+                                .size(14),
+                            text_input("Proxy password", &self.proxy_password)
+                                .secure(true)
+                                .on_input(|v| wrapper(
+                                    CustomizationMessage::SetProxyPassword(v)
+                                ))
+                                .size(14),
+                        ]
+                        .spacing(4),
+                    )
+                    .padding([4, 4]),
+                ),
+                menu::Item::new(
+                    container(
+                        column![
+                            text("CA certificate").size(14),
+                            text_input(
+                                "/path/to/ca-bundle.pem",
+                                &self.ca_certificate
+                            )
+                            .on_input(|v| wrapper(
+                                CustomizationMessage::SetCaCertificate(v)
+                            ))
+                            .size(14),
+                            text(
+                                "Trusted in addition to the system \
+                                 certificates, or alone where none are \
+                                 found. Only use a bundle from your IT \
+                                 department: whoever holds its key can \
+                                 read your Toggl traffic."
+                            )
+                            .size(12),
+                        ]
+                        .spacing(4),
+                    )
+                    .padding([4, 4]),
+                ),
+                toggle_item(
+                    "Confirm quit while tracking",
+                    self.confirm_quit,
+                    |v| wrapper(CustomizationMessage::SetConfirmQuit(v)),
+                ),
+                toggle_item(
+                    "Minimize instead of quitting",
+                    self.minimize_on_close,
+                    |v| wrapper(CustomizationMessage::SetMinimizeOnClose(v)),
+                ),
+                toggle_item(
+                    "Confirm replacing running timer",
+                    !self.skip_replace_confirmation,
+                    |v| {
+                        wrapper(
+                            CustomizationMessage::SetSkipReplaceConfirmation(
+                                !v,
+                            ),
+                        )
+                    },
+                ),
+                toggle_item(
+                    "Group similar entries",
+                    self.group_similar_entries,
+                    |v| {
+                        wrapper(CustomizationMessage::SetGroupSimilarEntries(v))
+                    },
+                ),
+                toggle_item(
+                    "Hide seconds of the running timer",
+                    self.calm_running_timer,
+                    |v| wrapper(CustomizationMessage::SetCalmRunningTimer(v)),
+                ),
+                toggle_item(
+                    "Save rounded times (a stop may move up to half a step \
+                     ahead)",
+                    self.round_on_save,
+                    |v| wrapper(CustomizationMessage::SetRoundOnSave(v)),
+                ),
+                toggle_item("Always on top", self.always_on_top, |v| {
+                    wrapper(CustomizationMessage::SetAlwaysOnTop(v))
+                }),
+                toggle_item("Start minimized", self.start_minimized, |v| {
+                    wrapper(CustomizationMessage::SetStartMinimized(v))
+                }),
+                toggle_item("Start on login", self.start_on_login, |v| {
+                    wrapper(CustomizationMessage::RequestStartOnLogin(v))
+                }),
+                toggle_item(
+                    "Verify edits with the server",
+                    self.verify_edits,
+                    |v| wrapper(CustomizationMessage::SetVerifyEdits(v)),
+                ),
             ])
-            .max_width(120.0),
+            .max_width(200.0),
+        )
+    }
+
+    fn idle_menu<'a, T: 'a + Clone>(
+        &'a self,
+        wrapper: &'a impl Fn(CustomizationMessage) -> T,
+    ) -> menu::Menu<'a, T, iced::Theme, iced::Renderer> {
+        menu::Menu::new(
+            IDLE_MINUTES
+                .iter()
+                .map(|m| {
+                    let label = if *m == 0 {
+                        "Never".to_string()
+                    } else {
+                        format!("After {m} min")
+                    };
+                    menu::Item::new(
+                        button(text(label))
+                            .width(iced::Length::Fill)
+                            .on_press_maybe(if self.idle_minutes == *m {
+                                None
+                            } else {
+                                Some(wrapper(
+                                    CustomizationMessage::SelectIdleMinutes(*m),
+                                ))
+                            }),
+                    )
+                })
+                .collect(),
         )
+        .max_width(120f32)
+    }
+
+    fn post_stop_menu<'a, T: 'a + Clone>(
+        &'a self,
+        wrapper: &'a impl Fn(CustomizationMessage) -> T,
+    ) -> menu::Menu<'a, T, iced::Theme, iced::Renderer> {
+        menu::Menu::new(
+            PostStopAction::VALUES
+                .iter()
+                .map(|a| {
+                    menu::Item::new(
+                        button(text(a.to_string()))
+                            .width(iced::Length::Fill)
+                            .on_press_maybe(if self.post_stop_action == *a {
+                                None
+                            } else {
+                                Some(wrapper(
+                                    CustomizationMessage::SelectPostStopAction(
+                                        *a,
+                                    ),
+                                ))
+                            }),
+                    )
+                })
+                .collect(),
+        )
+        .max_width(180f32)
     }
 
     fn time_format_menu<'a, T: 'a + Clone>(
@@ -224,4 +1001,306 @@ impl Customization {
         )
         .max_width(120f32)
     }
+
+    fn duration_format_menu<'a, T: 'a + Clone>(
+        &'a self,
+        wrapper: &'a impl Fn(CustomizationMessage) -> T,
+    ) -> menu::Menu<'a, T, iced::Theme, iced::Renderer> {
+        menu::Menu::new(
+            DurationFormat::VALUES
+                .iter()
+                .map(|f| {
+                    menu::Item::new(
+                        button(text(f.to_string()))
+                            .width(iced::Length::Fill)
+                            .on_press_maybe(if self.duration_format == *f {
+                                None
+                            } else {
+                                Some(wrapper(
+                                    CustomizationMessage::SelectDurationFormat(
+                                        *f,
+                                    ),
+                                ))
+                            }),
+                    )
+                })
+                .collect(),
+        )
+        .max_width(120f32)
+    }
+
+    fn nudge_step_menu<'a, T: 'a + Clone>(
+        &'a self,
+        wrapper: &'a impl Fn(CustomizationMessage) -> T,
+    ) -> menu::Menu<'a, T, iced::Theme, iced::Renderer> {
+        menu::Menu::new(
+            NudgeStep::VALUES
+                .iter()
+                .map(|s| {
+                    menu::Item::new(
+                        button(text(s.to_string()))
+                            .width(iced::Length::Fill)
+                            .on_press_maybe(if self.nudge_step == *s {
+                                None
+                            } else {
+                                Some(wrapper(
+                                    CustomizationMessage::SelectNudgeStep(*s),
+                                ))
+                            }),
+                    )
+                })
+                .collect(),
+        )
+        .max_width(120f32)
+    }
+
+    fn rounding_menu<'a, T: 'a + Clone>(
+        &'a self,
+        wrapper: &'a impl Fn(CustomizationMessage) -> T,
+    ) -> menu::Menu<'a, T, iced::Theme, iced::Renderer> {
+        menu::Menu::new(
+            RoundingMode::VALUES
+                .iter()
+                .map(|r| {
+                    menu::Item::new(
+                        button(text(r.to_string()))
+                            .width(iced::Length::Fill)
+                            .on_press_maybe(if self.rounding == *r {
+                                None
+                            } else {
+                                Some(wrapper(
+                                    CustomizationMessage::SelectRounding(*r),
+                                ))
+                            }),
+                    )
+                })
+                .collect(),
+        )
+        .max_width(120f32)
+    }
+
+    fn columns_menu<'a, T: 'a + Clone>(
+        &'a self,
+        wrapper: &'a impl Fn(CustomizationMessage) -> T,
+    ) -> menu::Menu<'a, T, iced::Theme, iced::Renderer> {
+        let columns = self.columns;
+        let set = move |edit: fn(&mut EntryColumns, bool)| {
+            move |v| {
+                let mut columns = columns;
+                edit(&mut columns, v);
+                wrapper(CustomizationMessage::SetColumns(columns))
+            }
+        };
+        menu::Menu::new(vec![
+            toggle_item("Project", columns.project, set(|c, v| c.project = v)),
+            toggle_item("Client", columns.client, set(|c, v| c.client = v)),
+            toggle_item("Tags", columns.tags, set(|c, v| c.tags = v)),
+            toggle_item(
+                "Billable",
+                columns.billable,
+                set(|c, v| c.billable = v),
+            ),
+        ])
+        .max_width(120f32)
+    }
+
+    fn total_period_menu<'a, T: 'a + Clone>(
+        &'a self,
+        wrapper: &'a impl Fn(CustomizationMessage) -> T,
+    ) -> menu::Menu<'a, T, iced::Theme, iced::Renderer> {
+        menu::Menu::new(
+            TotalPeriod::VALUES
+                .iter()
+                .map(|p| {
+                    menu::Item::new(
+                        button(text(p.to_string()))
+                            .width(iced::Length::Fill)
+                            .on_press_maybe(if self.total_period == *p {
+                                None
+                            } else {
+                                Some(wrapper(
+                                    CustomizationMessage::SelectTotalPeriod(*p),
+                                ))
+                            }),
+                    )
+                })
+                .chain(std::iter::once(menu::Item::new(
+                    container(
+                        text_input(
+                            &self.date_format.to_string(),
+                            &self.total_since,
+                        )
+                        .on_input(|v| {
+                            wrapper(CustomizationMessage::SetTotalSince(v))
+                        })
+                        .size(14),
+                    )
+                    .padding([4, 4]),
+                )))
+                .collect(),
+        )
+        .max_width(160f32)
+    }
+}
+
+/// Parse a comma-separated list of tags, dropping blanks and duplicates.
+pub fn split_tags(text: &str) -> Vec<String> {
+    merge_tags(
+        &[],
+        &text
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Append `extra` tags to `base`, skipping blanks and tags already present.
+pub fn merge_tags(base: &[String], extra: &[String]) -> Vec<String> {
+    let mut tags = Vec::with_capacity(base.len() + extra.len());
+    for tag in base.iter().chain(extra) {
+        if !tag.is_empty() && !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_relative_datetime() {
+        use chrono::TimeZone;
+
+        let now = Local.with_ymd_and_hms(2024, 6, 5, 12, 0, 0).unwrap();
+        let at = |d, h, m| Local.with_ymd_and_hms(2024, 6, d, h, m, 0).unwrap();
+        let parse = |text| parse_relative_datetime(text, now);
+        assert_eq!(parse("now"), Some(now));
+        assert_eq!(parse(" Now "), Some(now));
+        assert_eq!(parse("-15m"), Some(at(5, 11, 45)));
+        assert_eq!(parse("-1h 30m"), Some(at(5, 10, 30)));
+        assert_eq!(parse("+10m"), Some(at(5, 12, 10)));
+        assert_eq!(parse("09:30"), Some(at(5, 9, 30)));
+        assert_eq!(parse("2:15 pm"), Some(at(5, 14, 15)));
+        assert_eq!(parse("today 8:00"), Some(at(5, 8, 0)));
+        assert_eq!(parse("yesterday 14:00"), Some(at(4, 14, 0)));
+        assert_eq!(parse("yesterday"), None);
+        assert_eq!(parse("-soon"), None);
+        assert_eq!(parse("-9999999999999999:00"), None);
+        assert_eq!(parse("+200000000000000000h"), None);
+        assert_eq!(parse("25:00"), None);
+        assert_eq!(parse("05/06/2024 12:00"), None);
+
+        // The configured format still works as a fallback.
+        let customization = Customization::default();
+        let exact = customization.format_datetime(&Some(now));
+        assert_eq!(customization.parse_datetime(&exact), Ok(Some(now)));
+        assert!(customization.parse_datetime("whenever").is_err());
+    }
+
+    #[test]
+    fn test_with_overrides() {
+        let saved = Customization {
+            confirm_quit: true,
+            ..Customization::default()
+        };
+        let merged = saved
+            .with_overrides(
+                r#"{"duration_format": "Decimal", "columns": {"tags": true}}"#,
+            )
+            .unwrap();
+        assert_eq!(merged.duration_format, DurationFormat::Decimal);
+        assert!(merged.columns.tags);
+        // Fields the file doesn't mention keep their saved values.
+        assert!(merged.confirm_quit);
+
+        let err = saved.with_overrides(r#"{"no_such": 1}"#).unwrap_err();
+        assert!(err.contains("Unknown field 'no_such'"), "{err}");
+        let err = saved
+            .with_overrides(r#"{"date_format": "Ymd"}"#)
+            .unwrap_err();
+        assert!(err.contains("Invalid 'date_format'"), "{err}");
+        assert!(saved.with_overrides("[]").is_err());
+    }
+
+    #[test]
+    fn test_columns_default() {
+        let customization: Customization =
+            serde_json::from_str(r#"{"columns": {"tags": true}}"#).unwrap();
+        assert_eq!(
+            customization.columns,
+            EntryColumns {
+                tags: true,
+                ..EntryColumns::default()
+            }
+        );
+        assert!(Customization::default().columns.project);
+    }
+
+    #[test]
+    fn test_format_running() {
+        let mut customization = Customization::default();
+        let duration = Duration::seconds(3 * 3600 + 5 * 60 + 59);
+        assert_eq!(customization.format_running(&duration), "3:05:59");
+        customization.calm_running_timer = true;
+        assert_eq!(customization.format_running(&duration), "3:05");
+        assert_eq!(customization.format_running(&-duration), "0:00");
+    }
+
+    #[test]
+    fn test_round() {
+        use chrono::TimeZone;
+
+        let mut customization = Customization::default();
+        let duration = Duration::seconds(22 * 60 + 30);
+        assert_eq!(customization.round(&duration), duration);
+        customization.rounding = RoundingMode::Fifteen;
+        assert_eq!(customization.round(&duration), Duration::minutes(30));
+        customization.rounding = RoundingMode::Six;
+        assert_eq!(customization.round(&duration), Duration::minutes(24));
+        customization.rounding = RoundingMode::Five;
+        assert_eq!(customization.round(&duration), Duration::minutes(25));
+        assert_eq!(
+            customization.round(&Duration::minutes(2)),
+            Duration::minutes(5)
+        );
+        assert_eq!(customization.round(&Duration::zero()), Duration::zero());
+
+        let start = Local.with_ymd_and_hms(2024, 6, 3, 9, 0, 0).unwrap();
+        let stop = start + Duration::minutes(23);
+        assert_eq!(customization.stop_to_save(start, stop), stop);
+        customization.round_on_save = true;
+        assert_eq!(
+            customization.stop_to_save(start, stop),
+            start + Duration::minutes(25)
+        );
+    }
+
+    #[test]
+    fn test_format_total() {
+        let customization = Customization::default();
+        let committed = Duration::minutes(372);
+        assert_eq!(
+            customization.format_total(&committed, &Duration::zero()),
+            "6:12:00"
+        );
+        assert_eq!(
+            customization.format_total(&committed, &Duration::minutes(45)),
+            "6:12:00 (+0:45:00 running)"
+        );
+    }
+
+    #[test]
+    fn test_default_tags() {
+        let customization = Customization {
+            default_tags: " app, ,work,app ".to_string(),
+            ..Customization::default()
+        };
+        assert_eq!(customization.default_tags(), vec!["app", "work"]);
+        assert_eq!(
+            merge_tags(&["work".to_string()], &customization.default_tags()),
+            vec!["work", "app"]
+        );
+    }
 }
@@ -1,37 +1,90 @@
+use chrono::Local;
 use iced::widget::{
     button, column, container, pick_list, row, scrollable, text, text_editor,
     text_input,
 };
 use iced::{Element, Fill, Length, Right, Task as Command};
+use log::warn;
 
-use crate::client::Client;
+use crate::client::{Client, FailureKind};
 use crate::customization::Customization;
-use crate::project::{MaybeProject, Project};
-use crate::time_entry::TimeEntry;
+use crate::project::{
+    filter_projects, validate_new_project, MaybeProject, Project, ProjectId,
+    DEFAULT_PROJECT_COLOR,
+};
+use crate::rules::apply_rules;
+use crate::time_entry::{
+    duration_to_hms, parse_hms, validate_times, PendingChange, TimeEntry,
+};
+use crate::workspace::WorkspaceUser;
+
+/// Shown when the server kept other values than the ones just saved.
+pub const OUT_OF_SYNC: &str =
+    "The server kept a different version of the entry, showing it instead.";
 
 #[derive(Debug)]
 pub struct EditTimeEntry {
     entry: TimeEntry,
     api_token: String,
     description_content: text_editor::Content,
+    notes_content: text_editor::Content,
     start_text: String,
     stop_text: String,
+    /// Typed as h:mm:ss; editing it moves the stop.
+    duration_text: String,
+    /// Whether the texts currently parse, for live feedback.
+    start_valid: bool,
+    stop_valid: bool,
+    duration_valid: bool,
     error: Option<String>,
-    projects: Vec<MaybeProject>,
+    projects: Vec<Project>,
     selected_project: MaybeProject,
+    project_filter: String,
+    /// Members the entry can be assigned to; only loaded for admins.
+    users: Vec<WorkspaceUser>,
+    /// Name and color typed for a project to create, if the form is open.
+    new_project: Option<(String, String)>,
+    /// Project and tags chosen before rules matched the description, so that
+    /// rules matched on the way to the final text can be undone.
+    project_before_rules: Option<ProjectId>,
+    tags_before_rules: Vec<String>,
 }
 
 #[derive(Clone, Debug)]
 pub enum EditTimeEntryMessage {
     DescriptionEdited(text_editor::Action),
+    NotesEdited(text_editor::Action),
     ProjectSelected(MaybeProject),
+    ProjectFilterEdited(String),
+    /// Assign the best match for the project filter.
+    SelectTopProject,
     StartEdited(String),
     StopEdited(String),
+    DurationEdited(String),
+    /// Move the start by one nudge step; the sign gives the direction.
+    NudgeStart(i32),
+    /// Move the stop by one nudge step; the sign gives the direction.
+    NudgeStop(i32),
     Submit,
+    /// Save without leaving the editor.
+    Apply,
     Delete,
     Abort,
     Completed,
+    Applied(Box<TimeEntry>),
+    /// Saved, but the server returns other values: the version it has.
+    OutOfSync(Box<TimeEntry>),
+    Deleted,
+    /// Offline: the change is kept to send later.
+    Queued(Box<PendingChange>),
     Error(String),
+    UsersLoaded(Result<Vec<WorkspaceUser>, String>),
+    UserSelected(WorkspaceUser),
+    ToggleNewProject,
+    NewProjectNameEdited(String),
+    NewProjectColorEdited(String),
+    CreateProject,
+    ProjectCreated(Project),
 }
 
 impl EditTimeEntry {
@@ -40,6 +93,7 @@ impl EditTimeEntry {
         api_token: &str,
         customization: &Customization,
         projects: Vec<Project>,
+        notes: Option<String>,
     ) -> Self {
         let description = entry.description.clone();
         let start_text = customization.format_datetime(&Some(entry.start));
@@ -48,20 +102,94 @@ impl EditTimeEntry {
             .iter()
             .find(|p| Some(p.id) == entry.project_id)
             .cloned();
-        Self {
-            entry,
+        let mut screen = Self {
+            entry: entry.clone(),
             api_token: api_token.to_string(),
             description_content: text_editor::Content::with_text(
                 &description.unwrap_or("".to_string()),
             ),
+            notes_content: text_editor::Content::with_text(
+                &notes.unwrap_or_default(),
+            ),
             start_text,
             stop_text,
+            duration_text: String::new(),
+            start_valid: true,
+            stop_valid: true,
+            duration_valid: true,
             error: None,
-            projects: projects.into_iter().map(|p| p.into()).collect(),
+            projects,
             selected_project: selected_project.into(),
+            project_filter: String::new(),
+            users: vec![],
+            new_project: None,
+            project_before_rules: entry.project_id,
+            tags_before_rules: entry.tags.clone(),
+        };
+        screen.refresh_duration(customization);
+        screen
+    }
+
+    /// Fetch the workspace members to offer reassigning the entry.
+    pub fn load_users(&self) -> Command<EditTimeEntryMessage> {
+        let workspace_id = self.entry.workspace_id;
+        let client = Client::from_api_token(&self.api_token);
+        Command::future(async move {
+            EditTimeEntryMessage::UsersLoaded(
+                WorkspaceUser::load(workspace_id, &client)
+                    .await
+                    .map_err(|e| e.to_string()),
+            )
+        })
+    }
+
+    fn refresh_validity(&mut self, customization: &Customization) {
+        self.start_valid = matches!(
+            customization.parse_datetime(&self.start_text),
+            Ok(Some(_))
+        );
+        // An empty stop keeps the entry running.
+        self.stop_valid = customization.parse_datetime(&self.stop_text).is_ok();
+    }
+
+    /// The known project with this id, for the picker.
+    fn find_project(&self, id: Option<ProjectId>) -> MaybeProject {
+        self.projects
+            .iter()
+            .find(|p| Some(p.id) == id)
+            .cloned()
+            .into()
+    }
+
+    /// Show the duration between the typed start and stop.
+    fn refresh_duration(&mut self, customization: &Customization) {
+        if let (Ok(Some(start)), Ok(Some(stop))) = (
+            customization.parse_datetime(&self.start_text),
+            customization.parse_datetime(&self.stop_text),
+        ) {
+            if stop >= start {
+                self.duration_text = duration_to_hms(&(stop - start));
+                self.duration_valid = true;
+            }
+        } else if self.stop_text.trim().is_empty() {
+            // Running: show the time so far, read-only.
+            self.duration_text =
+                match customization.parse_datetime(&self.start_text) {
+                    Ok(Some(start)) => duration_to_hms(&(Local::now() - start)),
+                    _ => String::new(),
+                };
+            self.duration_valid = true;
         }
     }
 
+    pub fn entry_id(&self) -> u64 {
+        self.entry.id
+    }
+
+    pub fn notes(&self) -> String {
+        self.notes_content.text()
+    }
+
     pub fn view(&self) -> Element<EditTimeEntryMessage> {
         let content = column![
             column![button("X")
@@ -72,33 +200,115 @@ impl EditTimeEntry {
             text_editor(&self.description_content)
                 .on_action(EditTimeEntryMessage::DescriptionEdited),
             row![
+                nudge_button("-", EditTimeEntryMessage::NudgeStart(-1)),
                 text_input("Start", &self.start_text)
                     .id("start-input")
                     .on_input(EditTimeEntryMessage::StartEdited),
+                validity_mark(self.start_valid),
+                nudge_button("+", EditTimeEntryMessage::NudgeStart(1)),
+                nudge_button("-", EditTimeEntryMessage::NudgeStop(-1)),
                 text_input("Stop", &self.stop_text)
                     .id("end-input")
                     .on_input(EditTimeEntryMessage::StopEdited),
+                validity_mark(self.stop_valid),
+                nudge_button("+", EditTimeEntryMessage::NudgeStop(1)),
             ]
-            .spacing(20),
-            pick_list(
-                std::iter::once(MaybeProject::None)
-                    .chain(self.projects.clone().into_iter())
-                    .collect::<Vec<_>>(),
-                Some(self.selected_project.clone()),
-                EditTimeEntryMessage::ProjectSelected
-            ),
+            .spacing(5),
             row![
-                button("Save")
-                    .on_press(EditTimeEntryMessage::Submit)
-                    .style(button::primary)
-                    .width(Length::FillPortion(1)),
-                button("Delete")
-                    .on_press(EditTimeEntryMessage::Delete)
-                    .style(button::danger)
+                text("Duration").size(14),
+                text_input("h:mm:ss", &self.duration_text)
+                    .on_input_maybe(
+                        (!self.stop_text.trim().is_empty())
+                            .then_some(EditTimeEntryMessage::DurationEdited)
+                    )
+                    .width(Length::Fixed(120.0)),
+                validity_mark(self.duration_valid),
+            ]
+            .spacing(5)
+            .align_y(iced::Center),
+            row![
+                text_input("Type to find a project", &self.project_filter)
+                    .id("project-filter-input")
+                    .on_input(EditTimeEntryMessage::ProjectFilterEdited)
+                    .on_submit(EditTimeEntryMessage::SelectTopProject)
                     .width(Length::FillPortion(1)),
+                pick_list(
+                    std::iter::once(MaybeProject::None)
+                        .chain(
+                            filter_projects(
+                                &self.projects,
+                                &self.project_filter
+                            )
+                            .into_iter()
+                            .map(|p| p.clone().into())
+                        )
+                        .collect::<Vec<_>>(),
+                    Some(self.selected_project.clone()),
+                    EditTimeEntryMessage::ProjectSelected
+                )
+                .width(Length::FillPortion(1)),
+                button(text("+").center())
+                    .on_press(EditTimeEntryMessage::ToggleNewProject)
+                    .style(button::secondary)
+                    .width(Length::Fixed(28f32)),
             ]
-            .spacing(20),
+            .spacing(10),
         ]
+        .push_maybe(self.new_project.as_ref().map(|(name, color)| {
+            row![
+                text_input("New project name", name)
+                    .id("new-project-input")
+                    .on_input(EditTimeEntryMessage::NewProjectNameEdited)
+                    .on_submit(EditTimeEntryMessage::CreateProject)
+                    .width(Length::FillPortion(2)),
+                text_input("#rrggbb", color)
+                    .on_input(EditTimeEntryMessage::NewProjectColorEdited)
+                    .on_submit(EditTimeEntryMessage::CreateProject)
+                    .width(Length::FillPortion(1)),
+                button("Create")
+                    .on_press(EditTimeEntryMessage::CreateProject)
+                    .style(button::primary),
+            ]
+            .spacing(10)
+        }))
+        .push_maybe((!self.users.is_empty()).then(|| {
+            row![
+                text("User").size(14),
+                pick_list(
+                    self.users.as_slice(),
+                    self.users.iter().find(|u| u.id == self.entry.user_id),
+                    EditTimeEntryMessage::UserSelected,
+                )
+                .width(Fill),
+            ]
+            .spacing(10)
+            .align_y(iced::Center)
+        }))
+        .push(
+            column![
+                text("Notes (stored on this computer only)").size(14),
+                text_editor(&self.notes_content)
+                    .placeholder("Anything worth remembering about this entry")
+                    .height(100)
+                    .on_action(EditTimeEntryMessage::NotesEdited),
+                row![
+                    button("Save")
+                        .on_press(EditTimeEntryMessage::Submit)
+                        .style(button::primary)
+                        .width(Length::FillPortion(1)),
+                    button("Apply")
+                        .on_press(EditTimeEntryMessage::Apply)
+                        .style(button::secondary)
+                        .width(Length::FillPortion(1)),
+                    button("Delete")
+                        .on_press(EditTimeEntryMessage::Delete)
+                        .style(button::danger)
+                        .width(Length::FillPortion(1)),
+                ]
+                .spacing(20),
+            ]
+            .spacing(10),
+        )
         .push_maybe(self.error.clone().map(|e| text(e).style(text::danger)))
         .spacing(10);
 
@@ -114,55 +324,146 @@ impl EditTimeEntry {
             EditTimeEntryMessage::DescriptionEdited(action) => {
                 self.description_content.perform(action);
                 self.entry.description = Some(self.description_content.text());
+                let (project_id, tags) = apply_rules(
+                    &customization.rules,
+                    &self.description_content.text(),
+                    self.project_before_rules,
+                    &self.tags_before_rules,
+                );
+                if project_id != self.entry.project_id {
+                    self.entry.project_id = project_id;
+                    self.selected_project = self.find_project(project_id);
+                }
+                self.entry.set_tags(tags);
+            }
+            EditTimeEntryMessage::NotesEdited(action) => {
+                self.notes_content.perform(action);
             }
             EditTimeEntryMessage::StartEdited(start) => {
                 self.start_text = start;
+                self.refresh_validity(customization);
+                self.refresh_duration(customization);
             }
             EditTimeEntryMessage::StopEdited(stop) => {
                 self.stop_text = stop;
+                self.refresh_validity(customization);
+                self.refresh_duration(customization);
+            }
+            EditTimeEntryMessage::DurationEdited(duration_text) => {
+                self.duration_valid = false;
+                // A duration too long for a date leaves the stop as it was.
+                let stop = match (
+                    parse_hms(&duration_text),
+                    customization.parse_datetime(&self.start_text),
+                ) {
+                    (Ok(duration), Ok(Some(start))) => {
+                        start.checked_add_signed(duration)
+                    }
+                    _ => None,
+                };
+                if let Some(stop) = stop {
+                    self.stop_text = customization.format_datetime(&Some(stop));
+                    self.duration_valid = true;
+                    self.refresh_validity(customization);
+                }
+                self.duration_text = duration_text;
+            }
+            EditTimeEntryMessage::NudgeStart(direction) => {
+                let Ok(Some(start)) =
+                    customization.parse_datetime(&self.start_text)
+                else {
+                    return Command::done(EditTimeEntryMessage::Error(
+                        format!("Invalid start date: {}", self.start_text),
+                    ));
+                };
+                let mut start = start + customization.nudge_step() * direction;
+                if let Ok(Some(stop)) =
+                    customization.parse_datetime(&self.stop_text)
+                {
+                    start = start.min(stop);
+                }
+                self.start_text = customization.format_datetime(&Some(start));
+                self.refresh_validity(customization);
+                self.refresh_duration(customization);
+            }
+            EditTimeEntryMessage::NudgeStop(direction) => {
+                let Ok(stop) = customization.parse_datetime(&self.stop_text)
+                else {
+                    return Command::done(EditTimeEntryMessage::Error(
+                        format!("Invalid end date: {}", self.stop_text),
+                    ));
+                };
+                // A running entry has no stop to move.
+                if let Some(stop) = stop {
+                    let mut stop =
+                        stop + customization.nudge_step() * direction;
+                    if let Ok(Some(start)) =
+                        customization.parse_datetime(&self.start_text)
+                    {
+                        stop = stop.max(start);
+                    }
+                    self.stop_text = customization.format_datetime(&Some(stop));
+                    self.refresh_validity(customization);
+                    self.refresh_duration(customization);
+                }
+            }
+            EditTimeEntryMessage::ProjectFilterEdited(filter) => {
+                self.project_filter = filter;
+            }
+            EditTimeEntryMessage::SelectTopProject => {
+                if let Some(project) =
+                    filter_projects(&self.projects, &self.project_filter)
+                        .first()
+                {
+                    let project = (*project).clone();
+                    self.project_filter.clear();
+                    return Command::done(
+                        EditTimeEntryMessage::ProjectSelected(project.into()),
+                    );
+                }
             }
             EditTimeEntryMessage::ProjectSelected(p) => {
                 self.entry.project_id = match &p {
                     MaybeProject::Some(p) => Some(p.id),
                     MaybeProject::None => None,
                 };
+                self.project_before_rules = self.entry.project_id;
                 self.selected_project = p;
             }
-            EditTimeEntryMessage::Submit => {
-                match customization.parse_datetime(&self.start_text) {
-                    Err(_) => {
-                        return Command::done(EditTimeEntryMessage::Error(
-                            format!("Invalid start date: {}", self.start_text),
-                        ))
-                    }
-                    Ok(None) => {
-                        return Command::done(EditTimeEntryMessage::Error(
-                            "Start cannot be blank".to_string(),
-                        ))
-                    }
-                    Ok(Some(date)) => self.entry.start = date,
-                };
-                if let Ok(date) = customization.parse_datetime(&self.stop_text)
-                {
-                    self.entry.stop = date;
-                } else {
+            EditTimeEntryMessage::Submit | EditTimeEntryMessage::Apply => {
+                let close = matches!(message, EditTimeEntryMessage::Submit);
+                let Ok(start) = customization.parse_datetime(&self.start_text)
+                else {
                     return Command::done(EditTimeEntryMessage::Error(
-                        format!("Invalid end date: {}", self.stop_text),
+                        format!("Invalid start date: {}", self.start_text),
                     ));
                 };
-                let duration = self
-                    .entry
-                    .stop
-                    .map(|stop| (stop - self.entry.start).num_seconds());
-                if duration.unwrap_or(1) < 0 {
+                let Ok(stop) = customization.parse_datetime(&self.stop_text)
+                else {
                     return Command::done(EditTimeEntryMessage::Error(
-                        "Start must come before end!".to_string(),
+                        format!("Invalid end date: {}", self.stop_text),
                     ));
                 };
-                self.entry.duration = duration.unwrap_or(-1);
+                let stop = stop.zip(start).map(|(stop, start)| {
+                    customization.stop_to_save(start, stop)
+                });
+                match validate_times(start, stop, Local::now()) {
+                    Err(e) => {
+                        return Command::done(EditTimeEntryMessage::Error(
+                            e.to_string(),
+                        ))
+                    }
+                    Ok((start, duration)) => {
+                        self.entry.start = start;
+                        self.entry.stop = stop;
+                        self.entry.duration = duration;
+                    }
+                };
                 return Command::future(Self::submit(
                     self.entry.clone(),
                     self.api_token.clone(),
+                    close,
+                    customization.verify_edits,
                 ));
             }
             EditTimeEntryMessage::Delete => {
@@ -172,10 +473,98 @@ impl EditTimeEntry {
                 ));
             }
             EditTimeEntryMessage::Abort => {}
-            EditTimeEntryMessage::Completed => {}
+            EditTimeEntryMessage::Applied(entry) => {
+                self.description_content = text_editor::Content::with_text(
+                    entry.description.as_deref().unwrap_or(""),
+                );
+                self.start_text =
+                    customization.format_datetime(&Some(entry.start));
+                self.stop_text = customization.format_datetime(&entry.stop);
+                self.refresh_validity(customization);
+                self.refresh_duration(customization);
+                self.error = None;
+                // The server may have changed the project or tags.
+                self.selected_project = self.find_project(entry.project_id);
+                self.project_filter.clear();
+                self.project_before_rules = entry.project_id;
+                self.tags_before_rules.clone_from(&entry.tags);
+                self.entry = *entry;
+            }
+            EditTimeEntryMessage::OutOfSync(entry) => {
+                let show = self.update(
+                    EditTimeEntryMessage::Applied(entry),
+                    customization,
+                );
+                self.error = Some(OUT_OF_SYNC.to_string());
+                return show;
+            }
+            EditTimeEntryMessage::Completed
+            | EditTimeEntryMessage::Deleted
+            | EditTimeEntryMessage::Queued(_) => {}
             EditTimeEntryMessage::Error(err) => {
                 self.error = Some(err);
             }
+            EditTimeEntryMessage::UsersLoaded(Ok(users)) => {
+                self.users = users;
+            }
+            EditTimeEntryMessage::UsersLoaded(Err(e)) => {
+                // Reassigning is optional, editing works without it.
+                warn!("Failed to load workspace users: {e}");
+            }
+            EditTimeEntryMessage::UserSelected(user) => {
+                self.entry.user_id = user.id;
+            }
+            EditTimeEntryMessage::ToggleNewProject => {
+                if self.new_project.take().is_none() {
+                    self.new_project = Some((
+                        self.project_filter.trim().to_string(),
+                        DEFAULT_PROJECT_COLOR.to_string(),
+                    ));
+                    return text_input::focus("new-project-input");
+                }
+            }
+            EditTimeEntryMessage::NewProjectNameEdited(value) => {
+                if let Some((name, _)) = &mut self.new_project {
+                    *name = value;
+                }
+            }
+            EditTimeEntryMessage::NewProjectColorEdited(value) => {
+                if let Some((_, color)) = &mut self.new_project {
+                    *color = value;
+                }
+            }
+            EditTimeEntryMessage::CreateProject => {
+                let Some((name, color)) = &self.new_project else {
+                    return Command::none();
+                };
+                let (name, color) = match validate_new_project(name, color) {
+                    Ok(valid) => valid,
+                    Err(e) => {
+                        self.error = Some(e);
+                        return Command::none();
+                    }
+                };
+                let workspace_id = self.entry.workspace_id;
+                let client = Client::from_api_token(&self.api_token);
+                return Command::future(async move {
+                    match Project::create(workspace_id, &name, &color, &client)
+                        .await
+                    {
+                        Ok(project) => {
+                            EditTimeEntryMessage::ProjectCreated(project)
+                        }
+                        Err(e) => EditTimeEntryMessage::Error(e.to_string()),
+                    }
+                });
+            }
+            EditTimeEntryMessage::ProjectCreated(project) => {
+                self.new_project = None;
+                self.error = None;
+                self.projects.push(project.clone());
+                return Command::done(EditTimeEntryMessage::ProjectSelected(
+                    project.into(),
+                ));
+            }
         };
         Command::none()
     }
@@ -183,14 +572,36 @@ impl EditTimeEntry {
     async fn submit(
         entry: TimeEntry,
         api_token: String,
+        close: bool,
+        verify: bool,
     ) -> EditTimeEntryMessage {
         let client = &Client::from_api_token(&api_token);
-        if let Err(message) =
-            entry.save(client).await.map_err(|e| e.to_string())
-        {
-            EditTimeEntryMessage::Error(message)
-        } else {
+        let saved = match entry.save(client).await {
+            Err(e) if FailureKind::is_offline(&e) => {
+                return EditTimeEntryMessage::Queued(Box::new(
+                    PendingChange::Update(entry),
+                ))
+            }
+            Err(e) => return EditTimeEntryMessage::Error(e.to_string()),
+            Ok(saved) => saved,
+        };
+        if verify {
+            match TimeEntry::load_one(entry.id, client).await {
+                Ok(server) if !server.same_content(&entry) => {
+                    return EditTimeEntryMessage::OutOfSync(Box::new(server));
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    return EditTimeEntryMessage::Error(format!(
+                        "Saved, but couldn't check the result: {e}"
+                    ))
+                }
+            }
+        }
+        if close {
             EditTimeEntryMessage::Completed
+        } else {
+            EditTimeEntryMessage::Applied(Box::new(saved))
         }
     }
 
@@ -199,12 +610,145 @@ impl EditTimeEntry {
         api_token: String,
     ) -> EditTimeEntryMessage {
         let client = &Client::from_api_token(&api_token);
-        if let Err(message) =
-            entry.delete(client).await.map_err(|e| e.to_string())
-        {
-            EditTimeEntryMessage::Error(message)
-        } else {
-            EditTimeEntryMessage::Completed
+        match entry.clone().delete(client).await {
+            Err(e) if FailureKind::is_offline(&e) => {
+                EditTimeEntryMessage::Queued(Box::new(PendingChange::Delete(
+                    entry,
+                )))
+            }
+            Err(e) => EditTimeEntryMessage::Error(e.to_string()),
+            Ok(()) => EditTimeEntryMessage::Deleted,
+        }
+    }
+}
+
+/// Tick next to a datetime that parses; nothing while it is being typed,
+/// the error comes on save.
+fn validity_mark<'a>(valid: bool) -> text::Text<'a> {
+    text(if valid { "\u{2713}" } else { " " })
+        .style(text::success)
+        .width(Length::Fixed(12f32))
+}
+
+fn nudge_button(
+    label: &str,
+    message: EditTimeEntryMessage,
+) -> button::Button<'_, EditTimeEntryMessage> {
+    button(text(label).center())
+        .on_press(message)
+        .style(button::secondary)
+        .width(Length::Fixed(28f32))
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Local};
+
+    use super::*;
+    use crate::rules::Rule;
+    use crate::time_entry::test_entry;
+
+    #[test]
+    fn test_oversized_duration_is_invalid() {
+        let customization = Customization::default();
+        let start = Local::now() - Duration::hours(1);
+        let mut screen = EditTimeEntry::new(
+            test_entry(start, Some(start + Duration::minutes(30)), None),
+            "token",
+            &customization,
+            vec![],
+            None,
+        );
+        let stop_text = screen.stop_text.clone();
+
+        let _ = screen.update(
+            EditTimeEntryMessage::DurationEdited(
+                "99999999999:00:00".to_string(),
+            ),
+            &customization,
+        );
+        assert!(!screen.duration_valid);
+        assert_eq!(screen.duration_text, "99999999999:00:00");
+        assert_eq!(screen.stop_text, stop_text);
+
+        let _ = screen.update(
+            EditTimeEntryMessage::DurationEdited("1:00:00".to_string()),
+            &customization,
+        );
+        assert!(screen.duration_valid);
+        assert_ne!(screen.stop_text, stop_text);
+    }
+
+    #[test]
+    fn test_rules_follow_the_description() {
+        let mut customization = Customization::default();
+        customization.rules = vec![Rule {
+            pattern: "tea".to_string(),
+            tags: "break".to_string(),
+            ..Rule::default()
+        }];
+        let start = Local::now() - Duration::hours(1);
+        let mut entry = test_entry(start, Some(Local::now()), None);
+        entry.tags = vec!["work".to_string()];
+        entry.tag_ids = vec![7];
+        let mut screen =
+            EditTimeEntry::new(entry, "token", &customization, vec![], None);
+        let edit = |screen: &mut EditTimeEntry, edit| {
+            let _ = screen.update(
+                EditTimeEntryMessage::DescriptionEdited(
+                    text_editor::Action::Edit(edit),
+                ),
+                &customization,
+            );
+        };
+        for c in "tea".chars() {
+            edit(&mut screen, text_editor::Edit::Insert(c));
         }
+        edit(&mut screen, text_editor::Edit::Backspace);
+        assert_eq!(screen.entry.tags, vec!["work"]);
+
+        edit(&mut screen, text_editor::Edit::Insert('a'));
+        assert_eq!(screen.entry.tags, vec!["work", "break"]);
+        assert!(screen.entry.tag_ids.is_empty());
+    }
+
+    #[test]
+    fn test_out_of_sync_shows_server_version() {
+        let customization = Customization::default();
+        let start = Local::now() - Duration::hours(1);
+        let entry = test_entry(start, Some(Local::now()), None);
+        let project = Project {
+            id: ProjectId::from(5),
+            name: "Moved here".to_string(),
+            active: true,
+            color: DEFAULT_PROJECT_COLOR.to_string(),
+            workspace_id: Some(entry.workspace_id),
+            client_id: None,
+            client_name: None,
+        };
+        let mut screen = EditTimeEntry::new(
+            entry.clone(),
+            "token",
+            &customization,
+            vec![project.clone()],
+            None,
+        );
+        let mut server = entry;
+        server.description = Some("Kept by the server".to_string());
+        server.project_id = Some(project.id);
+        screen.project_filter = "typed".to_string();
+
+        let _ = screen.update(
+            EditTimeEntryMessage::OutOfSync(Box::new(server.clone())),
+            &customization,
+        );
+        assert_eq!(screen.entry, server);
+        assert_eq!(
+            screen.description_content.text().trim_end(),
+            "Kept by the server"
+        );
+        assert_eq!(screen.error.as_deref(), Some(OUT_OF_SYNC));
+        assert_eq!(screen.selected_project, MaybeProject::Some(project));
+        assert!(screen.project_filter.is_empty());
     }
 }
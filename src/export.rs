@@ -0,0 +1,206 @@
+#[cfg(feature = "gui")]
+use iced::widget::{
+    button, column, container, row, scrollable, text, text_input,
+};
+#[cfg(feature = "gui")]
+use iced::{Element, Fill, Length, Right, Task as Command};
+use log::info;
+
+use crate::customization::{Customization, DurationFormat};
+use crate::project::Project;
+use crate::time_entry::{format_duration, TimeEntry};
+
+/// Header line of the exported file.
+pub const COLUMNS: &str =
+    "date,start,stop,duration_seconds,duration,description,project,tags";
+
+/// Quote a CSV field if it contains a separator, a quote or a line break.
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Render `entries` as CSV, one line per entry after the [`COLUMNS`] header.
+pub fn export_csv(
+    entries: &[TimeEntry],
+    projects: &[Project],
+    customization: &Customization,
+) -> String {
+    let mut csv = format!("{COLUMNS}\n");
+    for entry in entries {
+        let project = entry
+            .project_id
+            .and_then(|id| projects.iter().find(|p| p.id == id))
+            .map_or("", |p| p.name.as_str());
+        let duration = entry.duration();
+        let fields = [
+            customization.format_date(&entry.start.date_naive()),
+            customization.format_datetime(&Some(entry.start)),
+            customization.format_datetime(&entry.stop),
+            duration.num_seconds().max(0).to_string(),
+            format_duration(&duration, DurationFormat::Hms),
+            entry.description.clone().unwrap_or_default(),
+            project.to_string(),
+            entry.tags.join(";"),
+        ];
+        csv.push_str(
+            &fields
+                .iter()
+                .map(|f| quote(f))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+    csv
+}
+
+pub async fn write_file(path: &str, contents: String) -> Result<(), String> {
+    async_std::fs::write(path, contents)
+        .await
+        .map_err(|e| format!("Can't write {path}: {e}"))?;
+    info!("Exported entries to {path}.");
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Debug)]
+pub struct ExportScreen {
+    /// The rendered file, fixed when the screen opens.
+    contents: String,
+    count: usize,
+    path: String,
+    in_progress: bool,
+    report: Option<String>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Debug)]
+pub enum ExportMessage {
+    PathEdited(String),
+    Export,
+    Done(String),
+    Abort,
+}
+
+#[cfg(feature = "gui")]
+impl ExportScreen {
+    pub fn new(
+        entries: &[TimeEntry],
+        projects: &[Project],
+        customization: &Customization,
+    ) -> Self {
+        Self {
+            contents: export_csv(entries, projects, customization),
+            count: entries.len(),
+            path: String::new(),
+            in_progress: false,
+            report: None,
+        }
+    }
+
+    pub fn view(&self) -> Element<ExportMessage> {
+        let content = column![
+            column![button("X")
+                .on_press(ExportMessage::Abort)
+                .style(button::text),]
+            .align_x(Right)
+            .width(Fill),
+            text("Export entries to CSV").size(18),
+            text(format!(
+                "{} loaded entries will be saved with columns: {COLUMNS}.",
+                self.count
+            ))
+            .size(12),
+            row![
+                text_input("Path to save the CSV file", &self.path)
+                    .on_input(ExportMessage::PathEdited)
+                    .on_submit(ExportMessage::Export),
+                button("Export")
+                    .on_press_maybe(
+                        (!self.in_progress && !self.path.trim().is_empty())
+                            .then_some(ExportMessage::Export)
+                    )
+                    .style(button::primary)
+                    .width(Length::Shrink),
+            ]
+            .spacing(10),
+        ]
+        .push_maybe(self.in_progress.then(|| text("Exporting...")))
+        .push_maybe(self.report.as_ref().map(text))
+        .spacing(10);
+
+        scrollable(container(content).center_x(Fill).padding(40)).into()
+    }
+
+    pub fn update(&mut self, message: ExportMessage) -> Command<ExportMessage> {
+        match message {
+            ExportMessage::PathEdited(path) => self.path = path,
+            ExportMessage::Export => {
+                if self.in_progress {
+                    return Command::none();
+                }
+                self.in_progress = true;
+                self.report = None;
+                let path = self.path.trim().to_string();
+                let contents = self.contents.clone();
+                let count = self.count;
+                return Command::future(async move {
+                    ExportMessage::Done(
+                        match write_file(&path, contents).await {
+                            Ok(()) => format!("Exported {count} entries."),
+                            Err(e) => e,
+                        },
+                    )
+                });
+            }
+            ExportMessage::Done(report) => {
+                self.in_progress = false;
+                self.report = Some(report);
+            }
+            ExportMessage::Abort => {}
+        }
+        Command::none()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Local, TimeZone};
+
+    use super::export_csv;
+    use crate::customization::Customization;
+    use crate::project::Project;
+    use crate::time_entry::test_entry;
+
+    #[test]
+    fn test_export_csv() {
+        let projects: Vec<Project> =
+            serde_json::from_value(serde_json::json!([
+                {"id": 1, "name": "Work", "active": true, "color": "#000000"},
+            ]))
+            .unwrap();
+        let start = Local.with_ymd_and_hms(2024, 6, 3, 9, 0, 0).unwrap();
+        let stop = start + chrono::Duration::minutes(90);
+        let mut described = test_entry(start, Some(stop), Some(1));
+        described.description = Some("Say \"hi\", then go".to_string());
+        described.tags = vec!["a".to_string(), "b".to_string()];
+        let mut blank = test_entry(start, Some(stop), None);
+        blank.description = None;
+        let entries = [described, blank];
+        let csv = export_csv(&entries, &projects, &Customization::default());
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(
+            lines[1].ends_with(
+                ",5400,1:30:00,\"Say \"\"hi\"\", then go\",Work,a;b"
+            ),
+            "{}",
+            lines[1]
+        );
+        assert!(lines[2].ends_with(",5400,1:30:00,,,"), "{}", lines[2]);
+    }
+}
@@ -0,0 +1,103 @@
+//! How long the user has not touched the keyboard or mouse, system-wide.
+
+use std::time::Duration;
+
+/// Parse `ioreg -c IOHIDSystem` output, which reports nanoseconds.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ioreg(output: &str) -> Option<Duration> {
+    output
+        .lines()
+        .find(|line| line.contains("\"HIDIdleTime\""))
+        .and_then(|line| line.rsplit('=').next())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_nanos)
+}
+
+/// Parse `xprintidle` output, which reports milliseconds.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_xprintidle(output: &str) -> Option<Duration> {
+    output.trim().parse().ok().map(Duration::from_millis)
+}
+
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "macos")),
+    allow(dead_code)
+)]
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Idle time on X11, `None` if `xprintidle` is missing (e.g. on Wayland).
+#[cfg(target_os = "linux")]
+pub fn idle_time() -> Option<Duration> {
+    parse_xprintidle(&run("xprintidle", &[])?)
+}
+
+#[cfg(target_os = "macos")]
+pub fn idle_time() -> Option<Duration> {
+    parse_ioreg(&run("ioreg", &["-c", "IOHIDSystem", "-d", "4"])?)
+}
+
+#[cfg(target_os = "windows")]
+pub fn idle_time() -> Option<Duration> {
+    #[repr(C)]
+    struct LastInputInfo {
+        cb_size: u32,
+        dw_time: u32,
+    }
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetLastInputInfo(plii: *mut LastInputInfo) -> i32;
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetTickCount() -> u32;
+    }
+    let mut info = LastInputInfo {
+        cb_size: std::mem::size_of::<LastInputInfo>() as u32,
+        dw_time: 0,
+    };
+    // SAFETY: `info` is a properly sized LASTINPUTINFO owned by this frame.
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    // SAFETY: no arguments, always succeeds.
+    let now = unsafe { GetTickCount() };
+    Some(Duration::from_millis(now.wrapping_sub(info.dw_time).into()))
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "windows"
+)))]
+pub fn idle_time() -> Option<Duration> {
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{parse_ioreg, parse_xprintidle};
+
+    #[test]
+    fn test_parse_idle_outputs() {
+        let ioreg = "    | |   \"HIDIdleTime\" = 5000000000\n    | |   \
+                     \"HIDKeyboardModifierMappingPairs\" = ()\n";
+        assert_eq!(parse_ioreg(ioreg), Some(Duration::from_secs(5)));
+        assert_eq!(parse_ioreg("nothing here"), None);
+        assert_eq!(
+            parse_xprintidle("1500\n"),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(parse_xprintidle("couldn't open display"), None);
+    }
+}
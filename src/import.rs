@@ -0,0 +1,406 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime};
+#[cfg(feature = "gui")]
+use iced::widget::{
+    button, column, container, row, scrollable, text, text_input,
+};
+#[cfg(feature = "gui")]
+use iced::{Element, Fill, Length, Right, Task as Command};
+use log::{error, info};
+use serde::Serialize;
+
+use crate::client::Client;
+use crate::customization::split_tags;
+use crate::project::{Project, ProjectId};
+use crate::time_entry::{validate_times, CreateTimeEntry};
+use crate::workspace::WorkspaceId;
+
+/// Expected columns, also accepted as the (optional) header line.
+pub const COLUMNS: &str = "date,start,stop,description,project,tags";
+
+/// One valid CSV row, ready to be created.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ImportRow {
+    /// 1-based line number in the file, for reporting.
+    pub line: usize,
+    pub start: DateTime<Local>,
+    pub duration: i64,
+    pub description: Option<String>,
+    pub project_id: Option<ProjectId>,
+    pub tags: Vec<String>,
+}
+
+impl ImportRow {
+    pub fn to_entry(&self, workspace_id: WorkspaceId) -> CreateTimeEntry {
+        CreateTimeEntry::new(
+            self.description.clone(),
+            workspace_id,
+            self.project_id,
+            self.tags.clone(),
+        )
+        .with_times(self.start, self.duration)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub created: usize,
+    /// Rows that would be created, only filled in a dry run.
+    pub planned: Vec<ImportRow>,
+    /// Rows that were skipped or rejected, with the reason.
+    pub errors: Vec<String>,
+}
+
+impl std::fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.dry_run {
+            write!(
+                f,
+                "Dry run, nothing was created. Would import {} entries:",
+                self.planned.len()
+            )?;
+            for row in &self.planned {
+                write!(
+                    f,
+                    "\nLine {}: {} for {} min: {}",
+                    row.line,
+                    row.start.format("%Y-%m-%d %H:%M"),
+                    row.duration / 60,
+                    row.description.as_deref().unwrap_or("")
+                )?;
+            }
+        } else {
+            write!(f, "Imported {} entries.", self.created)?;
+        }
+        for error in &self.errors {
+            write!(f, "\n{error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Split one CSV line, honoring double quotes and `""` escapes.
+fn split_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quote".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+fn parse_time(text: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(text, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+        .map_err(|_| format!("invalid time '{text}', use HH:MM"))
+}
+
+fn parse_row(
+    fields: &[String],
+    projects: &[Project],
+    workspace_id: WorkspaceId,
+    now: DateTime<Local>,
+) -> Result<ImportRow, String> {
+    let [date, start, stop, description, project, tags] = fields else {
+        return Err(format!(
+            "expected 6 columns ({COLUMNS}), got {}",
+            fields.len()
+        ));
+    };
+    let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{date}', use YYYY-MM-DD"))?;
+    let start_time = parse_time(start.trim())?;
+    let stop_time = parse_time(stop.trim())?;
+    let to_local = |time| {
+        date.and_time(time)
+            .and_local_timezone(Local)
+            .earliest()
+            .ok_or_else(|| format!("{date} {time} does not exist here"))
+    };
+    let start = to_local(start_time)?;
+    let mut stop = to_local(stop_time)?;
+    // A stop before the start means the entry ran past midnight.
+    if stop_time < start_time {
+        stop += Duration::days(1);
+    }
+    let (start, duration) = validate_times(Some(start), Some(stop), now)
+        .map_err(|e| e.to_string())?;
+    let project = project.trim();
+    let project_id = if project.is_empty() {
+        None
+    } else {
+        let found = projects.iter().find(|p| {
+            p.name.eq_ignore_ascii_case(project)
+                && p.fits_workspace(workspace_id)
+        });
+        Some(
+            found
+                .ok_or_else(|| format!("unknown project '{project}'"))?
+                .id,
+        )
+    };
+    let description = description.trim();
+    Ok(ImportRow {
+        line: 0,
+        start,
+        duration,
+        description: (!description.is_empty()).then(|| description.to_string()),
+        project_id,
+        tags: split_tags(tags),
+    })
+}
+
+/// Parse the whole file; bad rows are reported instead of aborting.
+pub fn parse_csv(
+    contents: &str,
+    projects: &[Project],
+    workspace_id: WorkspaceId,
+    now: DateTime<Local>,
+) -> (Vec<ImportRow>, Vec<String>) {
+    let mut rows = vec![];
+    let mut errors = vec![];
+    for (i, line) in contents.lines().enumerate() {
+        let line_no = i + 1;
+        if line.trim().is_empty()
+            || (i == 0 && line.trim().to_lowercase().starts_with("date"))
+        {
+            continue;
+        }
+        match split_line(line)
+            .and_then(|fields| parse_row(&fields, projects, workspace_id, now))
+        {
+            Ok(row) => rows.push(ImportRow {
+                line: line_no,
+                ..row
+            }),
+            Err(e) => errors.push(format!("Line {line_no}: {e}")),
+        }
+    }
+    (rows, errors)
+}
+
+/// Create the parsed rows one by one, pausing between requests.
+pub async fn create_rows(
+    rows: &[ImportRow],
+    workspace_id: WorkspaceId,
+    client: &Client,
+    report: &mut ImportReport,
+) {
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            async_std::task::sleep(Client::BULK_THROTTLE).await;
+        }
+        match row.to_entry(workspace_id).create(client).await {
+            Ok(_) => report.created += 1,
+            Err(e) => {
+                error!("Failed to import line {}: {e}", row.line);
+                report.errors.push(format!("Line {}: {e}", row.line));
+            }
+        }
+    }
+}
+
+pub async fn import_file(
+    path: &str,
+    projects: &[Project],
+    workspace_id: WorkspaceId,
+    client: &Client,
+    dry_run: bool,
+) -> Result<ImportReport, String> {
+    let contents = async_std::fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("Can't read {path}: {e}"))?;
+    let (rows, errors) =
+        parse_csv(&contents, projects, workspace_id, Local::now());
+    info!(
+        "Importing {} rows ({} invalid)...",
+        rows.len(),
+        errors.len()
+    );
+    let mut report = ImportReport {
+        dry_run,
+        errors,
+        ..ImportReport::default()
+    };
+    if dry_run {
+        report.planned = rows;
+    } else {
+        create_rows(&rows, workspace_id, client, &mut report).await;
+    }
+    Ok(report)
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Debug)]
+pub struct ImportScreen {
+    api_token: String,
+    workspace_id: WorkspaceId,
+    projects: Vec<Project>,
+    path: String,
+    in_progress: bool,
+    report: Option<String>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Debug)]
+pub enum ImportMessage {
+    PathEdited(String),
+    Import,
+    Done(String),
+    Abort,
+}
+
+#[cfg(feature = "gui")]
+impl ImportScreen {
+    pub fn new(
+        api_token: &str,
+        workspace_id: WorkspaceId,
+        projects: Vec<Project>,
+    ) -> Self {
+        Self {
+            api_token: api_token.to_string(),
+            workspace_id,
+            projects,
+            path: String::new(),
+            in_progress: false,
+            report: None,
+        }
+    }
+
+    pub fn view(&self) -> Element<ImportMessage> {
+        let content = column![
+            column![button("X")
+                .on_press(ImportMessage::Abort)
+                .style(button::text),]
+            .align_x(Right)
+            .width(Fill),
+            text("Import entries from CSV").size(18),
+            text(format!(
+                "Columns: {COLUMNS}. Dates are YYYY-MM-DD, times HH:MM, \
+                 tags are comma-separated."
+            ))
+            .size(12),
+            row![
+                text_input("Path to a CSV file", &self.path)
+                    .on_input(ImportMessage::PathEdited)
+                    .on_submit(ImportMessage::Import),
+                button("Import")
+                    .on_press_maybe(
+                        (!self.in_progress && !self.path.trim().is_empty())
+                            .then_some(ImportMessage::Import)
+                    )
+                    .style(button::primary)
+                    .width(Length::Shrink),
+            ]
+            .spacing(10),
+        ]
+        .push_maybe(self.in_progress.then(|| text("Importing...")))
+        .push_maybe(self.report.as_ref().map(text))
+        .spacing(10);
+
+        scrollable(container(content).center_x(Fill).padding(40)).into()
+    }
+
+    pub fn update(&mut self, message: ImportMessage) -> Command<ImportMessage> {
+        match message {
+            ImportMessage::PathEdited(path) => self.path = path,
+            ImportMessage::Import => {
+                if self.in_progress {
+                    return Command::none();
+                }
+                self.in_progress = true;
+                self.report = None;
+                let path = self.path.trim().to_string();
+                let projects = self.projects.clone();
+                let workspace_id = self.workspace_id;
+                let token = self.api_token.clone();
+                return Command::future(async move {
+                    let client = Client::from_api_token(&token);
+                    let report = import_file(
+                        &path,
+                        &projects,
+                        workspace_id,
+                        &client,
+                        false,
+                    )
+                    .await;
+                    ImportMessage::Done(match report {
+                        Ok(report) => report.to_string(),
+                        Err(e) => e,
+                    })
+                });
+            }
+            ImportMessage::Done(report) => {
+                self.in_progress = false;
+                self.report = Some(report);
+            }
+            ImportMessage::Abort => {}
+        }
+        Command::none()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Local, TimeZone};
+
+    use super::{parse_csv, split_line};
+    use crate::project::Project;
+
+    #[test]
+    fn test_split_line() {
+        assert_eq!(
+            split_line(r#"a,"b, c","say ""hi""",,"#).unwrap(),
+            vec!["a", "b, c", r#"say "hi""#, "", ""]
+        );
+        assert!(split_line(r#"a,"b"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let projects: Vec<Project> =
+            serde_json::from_value(serde_json::json!([
+                {"id": 1, "name": "Work", "active": true, "color": "#000000"},
+            ]))
+            .unwrap();
+        let now = Local.with_ymd_and_hms(2024, 6, 10, 12, 0, 0).unwrap();
+        let contents = "\
+date,start,stop,description,project,tags
+2024-06-03,09:00,10:30,Standup,work,\"a, b\"
+
+2024-06-03,23:30,00:30,Late,,
+2024-06-03,09:00,10:00,Bad,Unknown,
+2024-13-03,09:00,10:00,Bad date,,
+2024-06-03,09:00,10:00,Too few
+2024-06-11,09:00,10:00,Future,,
+";
+        let (rows, errors) = parse_csv(contents, &projects, 1.into(), now);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].duration, 90 * 60);
+        assert_eq!(rows[0].project_id, Some(1.into()));
+        assert_eq!(rows[0].tags, vec!["a", "b"]);
+        assert_eq!(rows[1].duration, 60 * 60);
+        assert_eq!(rows[1].project_id, None);
+        let lines: Vec<_> = errors
+            .iter()
+            .map(|e| e.split(':').next().unwrap())
+            .collect();
+        assert_eq!(lines, vec!["Line 5", "Line 6", "Line 7", "Line 8"]);
+        assert!(errors[0].contains("unknown project"), "{errors:?}");
+    }
+}
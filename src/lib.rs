@@ -0,0 +1,15 @@
+//! Toggl Track entities and API client behind the `toggl-tracker` app.
+//!
+//! Build with `default-features = false` to leave out the iced GUI parts.
+
+pub mod client;
+#[cfg(feature = "gui")]
+pub mod components;
+pub mod customization;
+pub mod export;
+pub mod import;
+pub mod project;
+pub mod related_info;
+pub mod rules;
+pub mod time_entry;
+pub mod workspace;
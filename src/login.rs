@@ -1,4 +1,6 @@
-use iced::widget::{button, column, container, scrollable, text, text_input};
+use iced::widget::{
+    button, column, container, row, scrollable, text, text_input,
+};
 use iced::{Element, Fill, Task as Command};
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +11,8 @@ pub struct LoginScreen {
     email: String,
     password: String,
     error: String,
+    /// Result of a successful connection test.
+    info: String,
 }
 
 #[derive(Clone, Debug)]
@@ -16,6 +20,9 @@ pub enum LoginScreenMessage {
     EmailEdited(String),
     PasswordEdited(String),
     Submit,
+    /// Check the credentials without logging in.
+    TestConnection,
+    ConnectionOk,
     Completed(String),
     Error(String),
     TabPressed(bool),
@@ -37,9 +44,16 @@ impl LoginScreen {
                 .secure(true)
                 .on_submit(LoginScreenMessage::Submit)
                 .on_input(LoginScreenMessage::PasswordEdited),
-            button("Login")
-                .on_press(LoginScreenMessage::Submit)
-                .style(button::primary),
+            row![
+                button("Login")
+                    .on_press(LoginScreenMessage::Submit)
+                    .style(button::primary),
+                button("Test connection")
+                    .on_press(LoginScreenMessage::TestConnection)
+                    .style(button::secondary),
+            ]
+            .spacing(10),
+            text(&self.info).style(text::success),
             text(&self.error).style(text::danger)
         ]
         .spacing(10);
@@ -56,10 +70,22 @@ impl LoginScreen {
             LoginScreenMessage::PasswordEdited(password) => {
                 self.password = password
             }
-            LoginScreenMessage::Error(err) => self.error = err,
+            LoginScreenMessage::Error(err) => {
+                self.info.clear();
+                self.error = err;
+            }
             LoginScreenMessage::Submit => {
                 return Command::future(self.clone().submit());
             }
+            LoginScreenMessage::TestConnection => {
+                self.info = "Connecting...".to_string();
+                self.error.clear();
+                return Command::future(self.clone().test_connection());
+            }
+            LoginScreenMessage::ConnectionOk => {
+                self.info = "Connected to Toggl, the credentials are valid."
+                    .to_string();
+            }
             LoginScreenMessage::Completed(_) => {}
             LoginScreenMessage::TabPressed(is_shift) => {
                 return if is_shift {
@@ -72,16 +98,19 @@ impl LoginScreen {
         Command::none()
     }
 
-    async fn submit(self) -> LoginScreenMessage {
+    fn validate(&self) -> Result<(), String> {
         if self.email.is_empty() {
-            return LoginScreenMessage::Error(
-                "Email must not be empty".to_string(),
-            );
+            return Err("Email must not be empty".to_string());
         }
         if self.password.is_empty() {
-            return LoginScreenMessage::Error(
-                "Password must not be empty".to_string(),
-            );
+            return Err("Password must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    async fn submit(self) -> LoginScreenMessage {
+        if let Err(e) = self.validate() {
+            return LoginScreenMessage::Error(e);
         }
         match Self::call_submit(&self.email, &self.password)
             .await
@@ -92,6 +121,19 @@ impl LoginScreen {
         }
     }
 
+    /// Same request as the login, but the token is thrown away.
+    async fn test_connection(self) -> LoginScreenMessage {
+        if let Err(e) = self.validate() {
+            return LoginScreenMessage::Error(e);
+        }
+        match Self::call_submit(&self.email, &self.password).await {
+            Ok(_) => LoginScreenMessage::ConnectionOk,
+            Err(e) => LoginScreenMessage::Error(format!(
+                "Connection test failed: {e}"
+            )),
+        }
+    }
+
     async fn call_submit(email: &str, password: &str) -> NetResult<String> {
         let client = Client::from_email_password(email, password);
         let mut rsp = client
@@ -1,8 +1,11 @@
-use components::menu_button;
-use customization::{Customization, CustomizationMessage};
+use components::{menu_button, toggle_item};
+use customization::{
+    merge_tags, split_tags, Customization, CustomizationMessage,
+    PostStopAction, CONFIG_FILE,
+};
 use iced::widget::{
-    button, center, column, container, horizontal_rule, row, scrollable, text,
-    text_input,
+    button, center, checkbox, column, container, horizontal_rule, pick_list,
+    row, scrollable, text, text_input,
 };
 use iced::{window, Color};
 use iced::{Center, Element, Fill, Padding, Task as Command};
@@ -12,37 +15,84 @@ use lazy_static::lazy_static;
 use log::{debug, error, info, warn};
 
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-mod client;
-mod components;
-mod customization;
+mod autostart;
+mod cli;
 mod edit_time_entry;
+mod idle;
 mod login;
-mod project;
-mod related_info;
-mod time_entry;
-mod workspace;
+mod report;
+mod search;
+mod secret;
+mod summary;
+mod tray;
+
+use toggl_tracker::{
+    client, components, customization, export, import, project, related_info,
+    rules, time_entry, workspace,
+};
 
-use crate::client::Client;
-use crate::edit_time_entry::{EditTimeEntry, EditTimeEntryMessage};
+use crate::client::{Client, FailureKind};
+use crate::edit_time_entry::{
+    EditTimeEntry, EditTimeEntryMessage, OUT_OF_SYNC,
+};
+use crate::export::{ExportMessage, ExportScreen};
+use crate::import::{ImportMessage, ImportScreen};
 use crate::login::{LoginScreen, LoginScreenMessage};
-use crate::project::{Project, ProjectId};
-use crate::related_info::ExtendedMe;
+use crate::project::{filter_projects, MaybeProject, Project, ProjectId};
+use crate::related_info::{clock_skew_warning, ExtendedMe};
+use crate::report::{ReportMessage, ReportScreen};
+use crate::rules::{apply_rules, parse_quick_add, RulesMessage, RulesScreen};
+use crate::search::{SearchMessage, SearchScreen};
+use crate::secret::Secret;
+use crate::summary::{SummaryMessage, SummaryScreen};
 use crate::time_entry::CreateTimeEntry;
-use crate::time_entry::{TimeEntry, TimeEntryMessage};
+use crate::time_entry::{
+    completed_times, group_similar, is_already_stopped, parse_elapsed,
+    past_stop_time, sort_entries, total_between, until_next_minute,
+    until_next_second, EntryFlag, PendingChange, TimeEntry, TimeEntryMessage,
+    LOCAL_ENTRY_ID,
+};
+use crate::tray::TrayMessage;
 use crate::workspace::{Workspace, WorkspaceId};
 
 pub fn main() -> iced::Result {
     env_logger::init();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
     iced::application(App::title, App::update, App::view)
         .subscription(App::subscription)
-        .window_size((500.0, 600.0))
+        .window(window::Settings {
+            size: iced::Size::new(500.0, 600.0),
+            // Leave enough room for the menu and the running entry bar.
+            min_size: Some(iced::Size::new(300.0, 150.0)),
+            exit_on_close_request: false,
+            ..window::Settings::default()
+        })
         .run_with(App::new)
 }
 
+/// Layout version of `toggl.json`, see [`migrate_state`].
+const STATE_VERSION: u64 = 1;
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 struct State {
+    /// Layout of the file this was loaded from, 0 before it was recorded.
+    #[serde(default)]
+    version: u64,
+    /// Blank on disk when the token is kept in the OS keyring.
+    #[serde(default)]
     api_token: String,
+    #[serde(default)]
+    token_in_keyring: bool,
+    /// The proxy password is never in the file, at most in the keyring.
+    #[serde(default)]
+    proxy_password_in_keyring: bool,
     time_entries: Vec<TimeEntry>,
     running_entry: Option<TimeEntry>,
     has_more_entries: bool,
@@ -51,9 +101,190 @@ struct State {
     default_workspace: Option<WorkspaceId>,
     default_project: Option<ProjectId>,
     customization: Customization,
+    /// Local-only notes keyed by entry id, never sent to Toggl.
+    #[serde(default)]
+    notes: HashMap<u64, String>,
+    /// Local-only flags keyed by entry id.
+    #[serde(default)]
+    flags: HashMap<u64, EntryFlag>,
+    /// Changes made offline, oldest first, sent once back online.
+    #[serde(default)]
+    pending_changes: Vec<PendingChange>,
+    #[serde(default)]
+    window: WindowGeometry,
+}
+
+/// Last size and position of the main window; `None` until it changed.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+struct WindowGeometry {
+    size: Option<(f32, f32)>,
+    position: Option<(f32, f32)>,
+}
+
+impl WindowGeometry {
+    /// The parts worth restoring, with the window kept on screen.
+    ///
+    /// iced doesn't tell which monitors there are, so the position is kept
+    /// right of and below the primary monitor's corner. This also undoes the
+    /// (-32000, -32000) that Windows reports for minimized windows.
+    fn restorable(&self) -> Self {
+        Self {
+            size: self
+                .size
+                .filter(|&(width, height)| width > 0.0 && height > 0.0),
+            position: self.position.map(|(x, y)| (x.max(0.0), y.max(0.0))),
+        }
+    }
 }
 
 impl State {
+    pub fn set_notes(&mut self, entry_id: u64, notes: String) {
+        if notes.trim().is_empty() {
+            self.notes.remove(&entry_id);
+        } else {
+            self.notes.insert(entry_id, notes);
+        }
+    }
+
+    pub fn set_flag(&mut self, entry_id: u64, flag: Option<EntryFlag>) {
+        if let Some(flag) = flag {
+            self.flags.insert(entry_id, flag);
+        } else {
+            self.flags.remove(&entry_id);
+        }
+    }
+
+    /// Replace a locally known entry with its updated version.
+    pub fn replace_entry(&mut self, entry: TimeEntry) {
+        if self
+            .running_entry
+            .as_ref()
+            .is_some_and(|e| e.id == entry.id)
+        {
+            if entry.is_running() {
+                self.running_entry = Some(entry);
+                return;
+            }
+            self.running_entry = None;
+            self.time_entries.insert(0, entry);
+        } else if let Some(existing) =
+            self.time_entries.iter_mut().find(|e| e.id == entry.id)
+        {
+            *existing = entry;
+        }
+        sort_entries(&mut self.time_entries);
+    }
+
+    /// Whether every entry started since `start` is loaded.
+    fn covers_since(&self, start: chrono::DateTime<chrono::Local>) -> bool {
+        !self.has_more_entries
+            || self.time_entries.last().is_some_and(|e| e.start <= start)
+    }
+
+    /// Show a queued change before the server has it; new entries get
+    /// [`LOCAL_ENTRY_ID`] until they are sent.
+    pub fn apply_change(&mut self, change: &PendingChange) {
+        match change {
+            PendingChange::Create(entry) => {
+                let entry = entry.preview();
+                if entry.is_running() {
+                    // The server stops the running entry when one starts.
+                    if let Some(running) = self.running_entry.take() {
+                        let stop = entry.start.max(running.start);
+                        self.time_entries.push(running.stopped_at(stop));
+                    }
+                    self.running_entry = Some(entry);
+                } else {
+                    self.time_entries.push(entry);
+                }
+                sort_entries(&mut self.time_entries);
+            }
+            PendingChange::Update(entry) => self.replace_entry(entry.clone()),
+            PendingChange::Delete(entry) => {
+                if self
+                    .running_entry
+                    .as_ref()
+                    .is_some_and(|e| e.id == entry.id)
+                {
+                    self.running_entry = None;
+                }
+                self.time_entries.retain(|e| e.id != entry.id);
+            }
+        }
+    }
+
+    /// Stop the running entry at `stop` if it was created offline, by
+    /// changing its queued creation. Returns whether it was.
+    pub fn stop_unsent_running(
+        &mut self,
+        stop: chrono::DateTime<chrono::Local>,
+    ) -> bool {
+        if self
+            .running_entry
+            .as_ref()
+            .is_none_or(|e| e.id != LOCAL_ENTRY_ID)
+        {
+            return false;
+        }
+        let Some(PendingChange::Create(entry)) =
+            self.pending_changes.iter_mut().rev().find(|change| {
+                matches!(change, PendingChange::Create(e) if e.is_running())
+            })
+        else {
+            return false;
+        };
+        entry.stop_at(stop);
+        self.running_entry = None;
+        self.time_entries.push(entry.preview());
+        sort_entries(&mut self.time_entries);
+        true
+    }
+
+    /// Whether an earlier sync left enough to render the entry list.
+    fn has_cached_data(&self) -> bool {
+        !self.workspaces.is_empty()
+    }
+
+    /// Entry to start for `text` typed into the new entry input, with
+    /// `@project`/`#tag` tokens, rules and the defaults applied.
+    pub fn new_entry(
+        &self,
+        text: &str,
+        default_tags: bool,
+    ) -> Result<CreateTimeEntry, String> {
+        let Some(workspace_id) = self.default_workspace else {
+            return Err("No workspace selected!".to_string());
+        };
+        let known_tags: Vec<_> = self
+            .time_entries
+            .iter()
+            .flat_map(|e| e.tags.iter().cloned())
+            .collect();
+        let quick =
+            parse_quick_add(text, &self.projects, workspace_id, &known_tags)?;
+        let tags = if default_tags {
+            merge_tags(&self.customization.default_tags(), &quick.tags)
+        } else {
+            quick.tags
+        };
+        // Rules are more specific than the default project, and an explicit
+        // @project beats both.
+        let (project_id, tags) = apply_rules(
+            &self.customization.rules,
+            &quick.description,
+            quick.project_id,
+            &tags,
+        );
+        let project_id =
+            quick.project_id.or(project_id).or(self.default_project);
+        Ok(CreateTimeEntry::new(
+            Some(quick.description),
+            workspace_id,
+            project_id,
+            tags,
+        ))
+    }
+
     pub fn update_from_context(self, me: ExtendedMe) -> Self {
         let ws_id = self
             .default_workspace
@@ -62,7 +293,7 @@ impl State {
         let project_id = self
             .default_project
             .filter(|&proj| me.projects.iter().any(|p| p.id == proj));
-        let (running_entry, time_entries) =
+        let (running_entry, mut time_entries) =
             TimeEntry::split_running(if let Some(ws_id) = ws_id {
                 me.time_entries
                     .into_iter()
@@ -71,6 +302,7 @@ impl State {
             } else {
                 me.time_entries
             });
+        sort_entries(&mut time_entries);
         Self {
             running_entry,
             time_entries,
@@ -87,6 +319,51 @@ impl State {
 #[derive(Debug, Default)]
 struct TemporaryState {
     new_running_entry_description: String,
+    /// Entry to duplicate once replacing the running timer is confirmed.
+    pending_duplicate: Option<Box<TimeEntry>>,
+    /// Elapsed time typed into the running bar to backdate the entry.
+    running_started_ago: String,
+    /// Typed stop time when stopping the running entry in the past.
+    stop_at: Option<String>,
+    /// Edited tags of the running entry, not saved yet.
+    running_tags: Option<String>,
+    /// Duration typed to create a stopped entry ending now.
+    new_entry_duration: String,
+    /// Why `new_entry_duration` can't be used.
+    new_entry_duration_error: Option<String>,
+    /// Do not attach the default tags to the next created entry.
+    skip_default_tags: bool,
+    expanded_groups: HashSet<u64>,
+    /// Projects whose entries are hidden from the list; `None` stands for
+    /// entries without a project.
+    hidden_projects: HashSet<Option<ProjectId>>,
+    /// Entries picked for a bulk action.
+    selected_entries: HashSet<u64>,
+    bulk_tag: String,
+    /// Entry whose right-click actions are shown.
+    context_menu: Option<u64>,
+    /// Only list entries with this text in the description or a tag.
+    filter: String,
+}
+
+impl TemporaryState {
+    fn is_visible(&self, entry: &TimeEntry) -> bool {
+        !self.hidden_projects.contains(&entry.project_id)
+            && entry.matches_filter(&self.filter)
+    }
+
+    fn toggle_group(&mut self, id: u64) {
+        if !self.expanded_groups.remove(&id) {
+            self.expanded_groups.insert(id);
+        }
+    }
+
+    /// Drop what was typed into the running bar for an entry that is gone.
+    fn forget_running_edits(&mut self) {
+        self.running_started_ago.clear();
+        self.stop_at = None;
+        self.running_tags = None;
+    }
 }
 
 #[derive(Debug, Default)]
@@ -95,39 +372,186 @@ struct App {
     screen: Screen,
     window_id: Option<window::Id>,
     error: String,
+    /// Set when the local clock disagrees with the server one.
+    clock_warning: Option<String>,
+    quit_requested: bool,
+    /// When the full data was last fetched from the server.
+    last_full_sync: Option<Instant>,
+    /// When refocusing the window last triggered a refresh.
+    last_focus_refresh: Option<Instant>,
+    /// An older page of entries is being fetched.
+    loading_more: bool,
+    /// Older pages fetched in a row to cover the menu total's period.
+    period_pages: usize,
+    /// Entries being saved in bulk, shown changed before the server
+    /// confirms; see [`App::is_pending`].
+    pending_entries: HashSet<u64>,
+    /// Open this entry in the editor once the next reload finishes.
+    edit_after_load: Option<u64>,
+    /// State writes that have not finished yet.
+    pending_saves: usize,
+    /// Quit once the pending writes finish.
+    quit_after_saving: bool,
+    /// When the system went idle with a timer running, while asking what to
+    /// do with that time.
+    idle_since: Option<chrono::DateTime<chrono::Local>>,
+    last_idle_check: Option<Instant>,
+    /// Queued offline changes are being sent.
+    flushing: bool,
+    last_flush_attempt: Option<Instant>,
+    /// When the window was last resized or moved, until that is saved.
+    geometry_changed: Option<Instant>,
 }
 
+/// Ignore focus changes closer to each other than this.
+const FOCUS_DEBOUNCE: Duration = Duration::from_secs(10);
+/// Refocusing after this long does a full sync, not just the running entry.
+const FULL_SYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// How often the system idle time is polled while a timer runs.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Scrolling closer than this many pixels to the end loads older entries.
+const LOAD_MORE_DISTANCE: f32 = 200.0;
+/// Older pages to fetch at most for the menu total; past that it stays
+/// marked as partial.
+const MAX_PERIOD_PAGES: usize = 10;
+/// Quiet period after resizing or moving the window before saving it.
+const GEOMETRY_SAVE_DELAY: Duration = Duration::from_secs(2);
+/// How often to retry sending offline changes while the timer ticks.
+const PENDING_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+/// Shown when editing an entry created offline before it is sent.
+const NOT_SENT_YET: &str =
+    "This entry is not on the server yet, edit it once back online.";
+/// Toggl has no per-entry page, the timer lists recent entries.
+const TOGGL_TIMER_URL: &str = "https://track.toggl.com/timer";
+
 #[derive(Debug, Default)]
 enum Screen {
     #[default]
     Loading,
     Unauthed(LoginScreen),
-    Authed,
+    /// Logged in, waiting for data; holds the cause if loading failed.
+    Authed(Option<(FailureKind, String)>),
     Loaded(TemporaryState),
     EditEntry(EditTimeEntry),
+    Search(SearchScreen),
+    Summary(SummaryScreen),
+    Report(ReportScreen),
+    Rules(RulesScreen),
+    Import(ImportScreen),
+    Export(ExportScreen),
 }
 
 #[derive(Debug, Clone)]
 enum Message {
     Loaded(Result<Box<State>, LoadError>),
-    DataFetched(Result<ExtendedMe, String>),
+    DataFetched(Result<ExtendedMe, (FailureKind, String)>),
+    RetryLoad,
+    /// Drop the cached entries but keep the login and settings.
+    ResetData,
+    LogOut,
+    WindowFocused,
+    TrayProxy(TrayMessage),
+    WindowResized(iced::Size),
+    WindowMoved(iced::Point),
+    /// The window moved while not minimized.
+    WindowPlaced(iced::Point),
+    /// Save the window geometry if it stopped changing.
+    SaveGeometry,
+    RefreshRunning,
+    RunningRefreshed(Option<Box<TimeEntry>>),
     LoginProxy(LoginScreenMessage),
     TimeEntryProxy(TimeEntryMessage),
     EditTimeEntryProxy(EditTimeEntryMessage),
     CustomizationProxy(CustomizationMessage),
+    SearchProxy(SearchMessage),
+    OpenSearch,
+    SummaryProxy(SummaryMessage),
+    OpenSummary,
+    ReportProxy(ReportMessage),
+    OpenReport,
+    RulesProxy(RulesMessage),
+    ImportProxy(ImportMessage),
+    OpenImport,
+    ExportProxy(ExportMessage),
+    OpenExport,
+    /// Show the directory with the state and config files.
+    OpenDataFolder,
+    CopyDataFolderPath,
     SetInitialRunningEntry(String),
     SubmitNewRunningEntry,
+    NewEntryDurationEdited(String),
+    SetSkipDefaultTags(bool),
     LoadMore,
-    LoadedMore(Vec<TimeEntry>),
+    /// The entry list moved; loads older entries near its end.
+    ListScrolled(scrollable::Viewport),
+    LoadedMore(Result<Vec<TimeEntry>, String>),
     Tick,
+    /// System idle time, `None` when it can't be queried here.
+    IdleChecked(Option<Duration>),
+    /// Stop the running entry when the idle time began.
+    DiscardIdle,
+    KeepIdle,
     Reload,
+    /// The running entry was stopped: reload and run the post-stop action.
+    Stopped(u64),
+    /// The entry was deleted on the server: drop its local data and reload.
+    Deleted(u64),
+    /// The shown data is stale: report it and reload.
+    OutOfDate(String),
     Discarded,
     Error(String),
     WindowIdReceived(Option<window::Id>),
     SelectWorkspace(WorkspaceId),
     SelectProject(Option<ProjectId>),
+    FilterProject(Option<ProjectId>, bool),
+    ShowAllProjects,
+    HideAllProjects,
+    /// Show only entries without a project and select them for a bulk
+    /// project assignment.
+    ShowUnassigned,
+    FilterChanged(String),
+    SelectEntry(u64, bool),
+    ClearSelection,
+    BulkTagEdited(String),
+    BulkTag {
+        /// Add the tag if set, remove it otherwise.
+        add: bool,
+    },
+    BulkProject(MaybeProject),
+    /// Bulk update finished; holds the original entries that failed.
+    BulkSaved {
+        /// Every entry the bulk action sent.
+        ids: Vec<u64>,
+        failed: Vec<(TimeEntry, String)>,
+        /// A saved entry differs on the server from what is shown.
+        out_of_sync: bool,
+    },
     TabPressed(bool),
     EscPressed,
+    DuplicateLatest,
+    /// Stop the running entry, or start a new one if none is running.
+    ToggleTimer,
+    FocusNewEntryInput,
+    CloseRequested(window::Id),
+    ConfirmDuplicate {
+        /// Don't ask again before replacing a running timer.
+        remember: bool,
+    },
+    CancelDuplicate,
+    RequestQuit,
+    StopAndQuit,
+    Quit,
+    /// A state write finished.
+    Saved,
+    /// A change failed for lack of connectivity: keep it to send later.
+    Queued(PendingChange),
+    /// The first `sent` queued changes are done with; `errors` are from the
+    /// ones the server rejected.
+    PendingFlushed {
+        sent: usize,
+        errors: Vec<String>,
+    },
+    CancelQuit,
 }
 
 lazy_static! {
@@ -141,10 +565,43 @@ lazy_static! {
         None
     )
     .expect("Icon must parse");
+    static ref TINTED_ICONS: Mutex<HashMap<String, window::Icon>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Running icon recolored with a project color, or `None` if the color
+/// cannot be parsed.
+fn tinted_icon(color: &str) -> Option<window::Icon> {
+    let mut cache = TINTED_ICONS.lock().expect("Icon cache poisoned");
+    if let Some(icon) = cache.get(color) {
+        return Some(icon.clone());
+    }
+    let tint = Color::parse(color)?;
+    let mut base =
+        image::load_from_memory(include_bytes!("../assets/icon-gray.png"))
+            .expect("Icon must parse")
+            .into_rgba8();
+    let (width, height) = base.dimensions();
+    for pixel in base.pixels_mut() {
+        // The gray icon supplies the shading, the project color the hue.
+        let [r, g, b, _] = pixel.0;
+        let luma =
+            (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0;
+        pixel.0[0] = (tint.r * luma * 255.0) as u8;
+        pixel.0[1] = (tint.g * luma * 255.0) as u8;
+        pixel.0[2] = (tint.b * luma * 255.0) as u8;
+    }
+    let icon = window::icon::from_rgba(base.into_raw(), width, height).ok()?;
+    cache.insert(color.to_string(), icon.clone());
+    Some(icon)
 }
 
 impl App {
     fn new() -> (Self, Command<Message>) {
+        // Environment-provided settings must apply to the login screen too.
+        Client::set_network_settings(
+            Customization::default().network_settings(),
+        );
         (
             Self::default(),
             Command::batch(vec![
@@ -163,55 +620,754 @@ impl App {
     }
 
     fn icon(&self) -> window::Icon {
-        if self.state.running_entry.is_some() {
-            RUNNING_ICON.clone()
+        if let Some(entry) = &self.state.running_entry {
+            entry
+                .project_id
+                .and_then(|id| self.state.projects.iter().find(|p| p.id == id))
+                .and_then(|p| tinted_icon(&p.color))
+                .unwrap_or_else(|| RUNNING_ICON.clone())
         } else {
             DEFAULT_ICON.clone()
         }
     }
 
+    fn apply_window_level(&self) -> Command<Message> {
+        let Some(id) = self.window_id else {
+            return Command::none();
+        };
+        window::change_level(
+            id,
+            if self.state.customization.always_on_top {
+                window::Level::AlwaysOnTop
+            } else {
+                window::Level::Normal
+            },
+        )
+    }
+
+    fn apply_start_minimized(&self) -> Command<Message> {
+        match self.window_id {
+            Some(id) if self.state.customization.start_minimized => {
+                window::minimize(id, true)
+            }
+            _ => Command::none(),
+        }
+    }
+
+    fn restore_window_geometry(&self) -> Command<Message> {
+        let Some(id) = self.window_id else {
+            return Command::none();
+        };
+        let WindowGeometry { size, position } = self.state.window.restorable();
+        Command::batch(vec![
+            size.map_or_else(Command::none, |(width, height)| {
+                window::resize(id, iced::Size::new(width, height))
+            }),
+            position.map_or_else(Command::none, |(x, y)| {
+                window::move_to(id, iced::Point::new(x, y))
+            }),
+        ])
+    }
+
     fn update_icon(&self) -> Command<Message> {
+        let tray = Command::future(tray::set_running(
+            self.state.running_entry.is_some(),
+        ))
+        .discard();
         if let Some(id) = self.window_id {
-            window::change_icon(id, self.icon())
+            Command::batch(vec![window::change_icon(id, self.icon()), tray])
+        } else {
+            tray
+        }
+    }
+
+    /// Whether the entry is shown with changes the server doesn't have yet,
+    /// being saved or queued while offline.
+    fn is_pending(&self, id: u64) -> bool {
+        self.pending_entries.contains(&id)
+            || self
+                .state
+                .pending_changes
+                .iter()
+                .any(|change| change.entry_id() == id)
+    }
+
+    fn show_window(&self) -> Command<Message> {
+        self.window_id.map_or_else(Command::none, show_window)
+    }
+
+    /// Override the customization with the optional config file.
+    fn apply_config(&mut self) {
+        let path = State::data_dir().join(CONFIG_FILE);
+        let Ok(json) = std::fs::read_to_string(&path) else {
+            return;
+        };
+        match self.state.customization.with_overrides(&json) {
+            Ok(customization) => {
+                info!("Applied {}.", path.display());
+                self.state.customization = customization;
+                Client::set_network_settings(
+                    self.state.customization.network_settings(),
+                );
+            }
+            Err(e) => {
+                error!("{e}");
+                self.error = e;
+            }
+        }
+    }
+
+    /// Keep paging back until the menu total covers its whole period, for
+    /// at most [`MAX_PERIOD_PAGES`] pages.
+    fn load_period_history(&mut self) -> Command<Message> {
+        let (_, start) =
+            self.state.customization.total_period(chrono::Local::now());
+        if self.state.covers_since(start) {
+            self.period_pages = 0;
+            Command::none()
+        } else if self.period_pages >= MAX_PERIOD_PAGES {
+            warn!("Stopped loading older entries, the total is partial.");
+            Command::none()
+        } else {
+            self.period_pages += 1;
+            Command::done(Message::LoadMore)
+        }
+    }
+
+    fn open_editor(&mut self, entry: TimeEntry) -> Command<Message> {
+        if entry.id == LOCAL_ENTRY_ID {
+            self.error = NOT_SENT_YET.to_string();
+            return Command::none();
+        }
+        let notes = self.state.notes.get(&entry.id).cloned();
+        // Only admins may move entries to other members.
+        let admin = self
+            .state
+            .workspaces
+            .iter()
+            .any(|ws| ws.id == entry.workspace_id && ws.admin);
+        let screen = EditTimeEntry::new(
+            entry,
+            &self.state.api_token,
+            &self.state.customization,
+            self.state.projects.clone(),
+            notes,
+        );
+        let init = if admin {
+            screen.load_users().map(Message::EditTimeEntryProxy)
         } else {
             Command::none()
+        };
+        self.screen = Screen::EditEntry(screen);
+        init
+    }
+
+    /// Open the entry requested by [`Message::Stopped`], now that it is
+    /// loaded with its final stop time.
+    fn edit_after_load(&mut self) -> Command<Message> {
+        let Some(id) = self.edit_after_load.take() else {
+            return Command::none();
+        };
+        match self.state.time_entries.iter().find(|e| e.id == id) {
+            Some(entry) => self.open_editor(entry.clone()),
+            None => {
+                warn!("Stopped entry {id} is not loaded, not editing it.");
+                Command::none()
+            }
         }
     }
 
+    /// Start a new entry like `entry`, stopping the running one if any.
+    fn duplicate(&self, mut entry: Box<TimeEntry>) -> Command<Message> {
+        // Copying paid-only fields into a free workspace fails with 402.
+        if !self
+            .state
+            .workspaces
+            .iter()
+            .any(|ws| ws.id == entry.workspace_id && ws.is_paid())
+        {
+            entry.billable = false;
+            entry.task_id = None;
+        }
+        let token = self.state.api_token.clone();
+        Command::future(async move {
+            let client = Client::from_api_token(&token);
+            match entry.continue_entry(&client).await {
+                Err(e) => {
+                    error!("Failed to duplicate an entry: {e}");
+                    Message::Error(e.to_string())
+                }
+                Ok(_) => {
+                    info!("Entry duplicated.");
+                    Message::Reload
+                }
+            }
+        })
+    }
+
+    /// Apply `edit` to the selected entries right away and save them one by
+    /// one; the ones the server rejects are rolled back by `BulkSaved`.
+    fn bulk_update(
+        &mut self,
+        selected: &HashSet<u64>,
+        edit: impl Fn(&mut TimeEntry),
+    ) -> Command<Message> {
+        let originals: Vec<_> = self
+            .state
+            .time_entries
+            .iter()
+            .filter(|e| selected.contains(&e.id))
+            .cloned()
+            .collect();
+        let mut updates = Vec::with_capacity(originals.len());
+        for original in originals {
+            let mut entry = original.clone();
+            edit(&mut entry);
+            if entry != original {
+                self.state.replace_entry(entry.clone());
+                updates.push((original, entry));
+            }
+        }
+        info!("Bulk updating {} entries...", updates.len());
+        let ids: Vec<_> = updates.iter().map(|(_, e)| e.id).collect();
+        self.pending_entries.extend(&ids);
+        let token = self.state.api_token.clone();
+        let verify = self.state.customization.verify_edits;
+        Command::future(async move {
+            let client = Client::from_api_token(&token);
+            let mut failed = vec![];
+            let mut out_of_sync = false;
+            for (i, (original, entry)) in updates.into_iter().enumerate() {
+                if i > 0 {
+                    async_std::task::sleep(Client::BULK_THROTTLE).await;
+                }
+                if let Err(e) = entry.save(&client).await {
+                    error!("Failed to update entry {}: {e}", entry.id);
+                    failed.push((original, e.to_string()));
+                } else if verify && !out_of_sync {
+                    out_of_sync = TimeEntry::load_one(entry.id, &client)
+                        .await
+                        .map_or(true, |server| !server.same_content(&entry));
+                }
+            }
+            Message::BulkSaved {
+                ids,
+                failed,
+                out_of_sync,
+            }
+        })
+    }
+
+    /// Save the running entry with a new start, keeping it running.
+    fn move_running_start(
+        &self,
+        start: chrono::DateTime<chrono::Local>,
+    ) -> Command<Message> {
+        // Never move a running entry's start into the future.
+        self.update_running(|entry| {
+            entry.start = start.min(chrono::Local::now())
+        })
+    }
+
+    /// Save the running entry changed by `edit`.
+    fn update_running(
+        &self,
+        edit: impl FnOnce(&mut TimeEntry),
+    ) -> Command<Message> {
+        let Some(mut entry) = self.state.running_entry.clone() else {
+            return Command::none();
+        };
+        if entry.id == LOCAL_ENTRY_ID {
+            return Command::done(Message::Error(NOT_SENT_YET.to_string()));
+        }
+        edit(&mut entry);
+        let token = self.state.api_token.clone();
+        let verify = self.state.customization.verify_edits;
+        Command::future(async move {
+            let client = Client::from_api_token(&token);
+            if let Err(e) = entry.save(&client).await {
+                return offline_or_error(e, PendingChange::Update(entry));
+            }
+            if verify
+                && TimeEntry::load_one(entry.id, &client)
+                    .await
+                    .map_or(true, |server| !server.same_content(&entry))
+            {
+                warn!("Running entry does not match the server, reloading.");
+                return Message::OutOfDate(OUT_OF_SYNC.to_string());
+            }
+            Message::Reload
+        })
+    }
+
+    /// Stop the running `entry` at a past `stop` time.
+    fn stop_running_at(
+        &mut self,
+        entry: TimeEntry,
+        stop: chrono::DateTime<chrono::Local>,
+    ) -> Command<Message> {
+        let stop = self.state.customization.stop_to_save(entry.start, stop);
+        if self.state.stop_unsent_running(stop) {
+            return self.save_unsent_stop();
+        }
+        let entry = entry.stopped_at(stop);
+        info!("Stopping running entry {} at {stop}...", entry.id);
+        let token = self.state.api_token.clone();
+        // `/stop` can only stop now, so save the chosen stop instead.
+        Command::future(async move {
+            let client = Client::from_api_token(&token);
+            match entry.save(&client).await {
+                Err(e) => {
+                    error!("Failed to stop a running entry: {e}");
+                    offline_or_error(e, PendingChange::Update(entry))
+                }
+                Ok(_) => Message::Stopped(entry.id),
+            }
+        })
+    }
+
+    /// Show an entry created offline as stopped, see
+    /// [`State::stop_unsent_running`].
+    fn save_unsent_stop(&mut self) -> Command<Message> {
+        info!("Stopped an entry that is not sent yet.");
+        Command::batch(vec![self.save_state(), self.update_icon()])
+    }
+
+    /// Save the window geometry once it stops changing for a while.
+    fn geometry_changed(&mut self) -> Command<Message> {
+        self.geometry_changed = Some(Instant::now());
+        Command::perform(async_std::task::sleep(GEOMETRY_SAVE_DELAY), |_| {
+            Message::SaveGeometry
+        })
+    }
+
+    /// Send the changes queued while offline, in order.
+    fn flush_pending(&mut self) -> Command<Message> {
+        if self.flushing || self.state.pending_changes.is_empty() {
+            return Command::none();
+        }
+        self.flushing = true;
+        self.last_flush_attempt = Some(Instant::now());
+        let changes = self.state.pending_changes.clone();
+        info!("Sending {} queued changes...", changes.len());
+        let token = self.state.api_token.clone();
+        Command::future(async move {
+            let client = Client::from_api_token(&token);
+            let mut sent = 0;
+            let mut errors = vec![];
+            for change in &changes {
+                if sent > 0 {
+                    async_std::task::sleep(Client::BULK_THROTTLE).await;
+                }
+                match change.send(&client).await {
+                    Err(e) if FailureKind::is_offline(&e) => break,
+                    Err(e) => {
+                        error!("Failed to send a queued change: {e}");
+                        errors.push(e.to_string());
+                    }
+                    Ok(()) => {}
+                }
+                sent += 1;
+            }
+            Message::PendingFlushed { sent, errors }
+        })
+    }
+
+    /// Poll the system idle time now and then while a timer runs.
+    fn check_idle(&mut self) -> Command<Message> {
+        if self.state.customization.idle_threshold().is_none()
+            || self.state.running_entry.is_none()
+            || self.idle_since.is_some()
+            || self
+                .last_idle_check
+                .is_some_and(|t| t.elapsed() < IDLE_CHECK_INTERVAL)
+        {
+            return Command::none();
+        }
+        self.last_idle_check = Some(Instant::now());
+        Command::future(async { Message::IdleChecked(idle::idle_time()) })
+    }
+
+    /// Persist the state, keeping track of the write so that quitting waits
+    /// for it.
+    fn save_state(&mut self) -> Command<Message> {
+        self.pending_saves += 1;
+        Command::perform(self.state.clone().save(), |_| Message::Saved)
+    }
+
+    fn leave_editor(&mut self) -> Command<Message> {
+        self.screen = Screen::Authed(None);
+        Command::batch(vec![
+            self.save_state(),
+            Command::future(Self::load_everything(
+                self.state.api_token.clone(),
+            )),
+        ])
+    }
+
     fn update(&mut self, message: Message) -> Command<Message> {
+        let running_id = self.state.running_entry.as_ref().map(|e| e.id);
+        let command = self.handle(message);
+        if self.state.running_entry.as_ref().map(|e| e.id) != running_id {
+            if let Screen::Loaded(temp_state) = &mut self.screen {
+                temp_state.forget_running_edits();
+            }
+        }
+        command
+    }
+
+    fn handle(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::WindowIdReceived(id) => {
                 debug!("Setting window id to {id:?}");
                 self.window_id = id;
                 if let Some(id) = id {
-                    return window::change_icon(id, self.icon());
+                    return Command::batch(vec![
+                        window::change_icon(id, self.icon()),
+                        self.apply_window_level(),
+                        self.restore_window_geometry(),
+                        self.apply_start_minimized(),
+                    ]);
                 };
             }
             Message::DataFetched(Ok(state)) => {
                 info!("Loaded initial data.");
-                match &self.screen {
-                    Screen::Loaded(_) => {}
-                    _ => {
-                        self.screen = Screen::Loaded(TemporaryState::default())
-                    }
-                };
+                self.last_full_sync = Some(Instant::now());
+                self.clock_warning = state.server_time.and_then(|server| {
+                    clock_skew_warning(server, chrono::Local::now())
+                });
+                if !matches!(self.screen, Screen::Loaded(_)) {
+                    self.screen = Screen::Loaded(TemporaryState::default())
+                }
                 self.state = self.state.clone().update_from_context(state);
+                // Changes still waiting to be sent are not on the server yet.
+                for change in self.state.pending_changes.clone() {
+                    self.state.apply_change(&change);
+                }
+                // The list may have been shown from the cache: forget
+                // projects and entries that are gone.
+                if let Screen::Loaded(temp_state) = &mut self.screen {
+                    let projects = &self.state.projects;
+                    temp_state.hidden_projects.retain(|id| {
+                        id.is_none_or(|id| projects.iter().any(|p| p.id == id))
+                    });
+                    let entries = &self.state.time_entries;
+                    temp_state
+                        .selected_entries
+                        .retain(|id| entries.iter().any(|e| e.id == *id));
+                }
+                // Only the first page is loaded again.
+                self.period_pages = 0;
                 return Command::batch(vec![
-                    Command::future(self.state.clone().save())
-                        .map(|_| Message::Discarded),
+                    self.save_state(),
                     self.update_icon(),
+                    self.load_period_history(),
+                    self.edit_after_load(),
+                    self.flush_pending(),
                 ]);
             }
-            Message::DataFetched(Err(e)) => {
-                error!("Failed to fetch initial data: {e}");
-                self.error = e;
+            Message::DataFetched(Err((kind, e))) => {
+                error!("Failed to fetch initial data ({kind:?}): {e}");
+                if let Screen::Authed(failure) = &mut self.screen {
+                    *failure = Some((kind, e));
+                } else {
+                    self.error = e;
+                }
                 return Command::none();
             }
+            Message::RetryLoad => {
+                self.screen = Screen::Authed(None);
+                return Command::future(Self::load_everything(
+                    self.state.api_token.clone(),
+                ));
+            }
+            Message::ResetData => {
+                info!("Resetting cached data.");
+                self.screen = Screen::Authed(None);
+                self.state = State {
+                    api_token: self.state.api_token.clone(),
+                    customization: self.state.customization.clone(),
+                    ..State::default()
+                };
+                return self.save_state().chain(Command::future(
+                    Self::load_everything(self.state.api_token.clone()),
+                ));
+            }
+            Message::LogOut => {
+                info!("Logging out.");
+                self.screen = Screen::Unauthed(LoginScreen::new());
+                self.state = State {
+                    customization: self.state.customization.clone(),
+                    window: self.state.window,
+                    ..State::default()
+                };
+                return self.save_state();
+            }
             Message::Error(e) => {
                 error!("Received generic error: {e}");
                 self.error = e;
                 return Command::none();
             }
+            Message::CloseRequested(id) => {
+                self.window_id = Some(id);
+                if self.state.customization.minimize_on_close {
+                    debug!("Minimizing instead of closing the window.");
+                    return window::minimize(id, true);
+                }
+                return Command::done(Message::RequestQuit);
+            }
+            Message::RequestQuit => {
+                if self.state.customization.confirm_quit
+                    && self.state.running_entry.is_some()
+                {
+                    info!("Asking for confirmation before quitting.");
+                    self.quit_requested = true;
+                    return Command::none();
+                }
+                return Command::done(Message::Quit);
+            }
+            Message::StopAndQuit => {
+                self.quit_requested = false;
+                if self.state.stop_unsent_running(chrono::Local::now()) {
+                    return self
+                        .save_unsent_stop()
+                        .chain(Command::done(Message::Quit));
+                }
+                let Some(entry) = self.state.running_entry.clone() else {
+                    return Command::done(Message::Quit);
+                };
+                info!("Stopping running entry {} before quit...", entry.id);
+                let token = self.state.api_token.clone();
+                let customization = self.state.customization.clone();
+                return Command::future(async move {
+                    let client = Client::from_api_token(&token);
+                    match entry.stop_rounded(&client, &customization).await {
+                        Err(e) if !is_already_stopped(&e) => {
+                            error!("Failed to stop a running entry: {e}");
+                            Message::Error(e.to_string())
+                        }
+                        _ => Message::Quit,
+                    }
+                });
+            }
+            Message::Tick => {
+                let retry = self
+                    .last_flush_attempt
+                    .is_none_or(|t| t.elapsed() >= PENDING_RETRY_INTERVAL);
+                return Command::batch(vec![
+                    self.check_idle(),
+                    if retry {
+                        self.flush_pending()
+                    } else {
+                        Command::none()
+                    },
+                ]);
+            }
+            Message::IdleChecked(idle) => {
+                let (Some(idle), Some(threshold), Some(entry)) = (
+                    idle,
+                    self.state.customization.idle_threshold(),
+                    &self.state.running_entry,
+                ) else {
+                    return Command::none();
+                };
+                let idle = chrono::Duration::from_std(idle).unwrap_or_default();
+                if idle >= threshold {
+                    info!("Idle for {} min.", idle.num_minutes());
+                    self.idle_since =
+                        Some((chrono::Local::now() - idle).max(entry.start));
+                }
+                return Command::none();
+            }
+            Message::DiscardIdle => {
+                let (Some(since), Some(entry)) =
+                    (self.idle_since.take(), self.state.running_entry.clone())
+                else {
+                    return Command::none();
+                };
+                return self.stop_running_at(entry, since);
+            }
+            Message::KeepIdle => {
+                self.idle_since = None;
+                return Command::none();
+            }
+            Message::Saved => {
+                self.pending_saves = self.pending_saves.saturating_sub(1);
+                if self.pending_saves == 0 && self.quit_after_saving {
+                    return Command::done(Message::Quit);
+                }
+                return Command::none();
+            }
+            Message::Queued(change) => {
+                warn!("Offline, the change will be sent later.");
+                if let PendingChange::Delete(entry) = &change {
+                    self.state.notes.remove(&entry.id);
+                    self.state.flags.remove(&entry.id);
+                }
+                self.state.apply_change(&change);
+                self.state.pending_changes.push(change);
+                return Command::batch(vec![
+                    self.save_state(),
+                    self.update_icon(),
+                ]);
+            }
+            Message::Deleted(id) => {
+                self.state.notes.remove(&id);
+                self.state.flags.remove(&id);
+                return Command::batch(vec![
+                    self.save_state(),
+                    Command::done(Message::Reload),
+                ]);
+            }
+            Message::PendingFlushed { sent, errors } => {
+                self.flushing = false;
+                // Logging out may have dropped the queue meanwhile.
+                let sent = sent.min(self.state.pending_changes.len());
+                self.state.pending_changes.drain(..sent);
+                if !errors.is_empty() {
+                    self.error = format!(
+                        "The server rejected {} offline changes: {}",
+                        errors.len(),
+                        errors.join("; ")
+                    );
+                }
+                if sent == 0 {
+                    return Command::none();
+                }
+                info!("Sent {sent} queued changes.");
+                return Command::batch(vec![
+                    self.save_state(),
+                    Command::future(Self::load_everything(
+                        self.state.api_token.clone(),
+                    )),
+                ]);
+            }
+            Message::TrayProxy(TrayMessage::StopRunning) => {
+                return Command::done(Message::TimeEntryProxy(
+                    TimeEntryMessage::StopRunning,
+                ));
+            }
+            Message::TrayProxy(TrayMessage::StartTimer) => {
+                return Command::done(Message::ToggleTimer);
+            }
+            Message::TrayProxy(TrayMessage::NewEntry) => {
+                return Command::batch(vec![
+                    self.show_window(),
+                    Command::done(Message::FocusNewEntryInput),
+                ]);
+            }
+            Message::TrayProxy(TrayMessage::ToggleWindow) => {
+                let Some(id) = self.window_id else {
+                    return Command::none();
+                };
+                // A minimized window still reports `Mode::Windowed`.
+                return window::get_mode(id)
+                    .then(move |mode| {
+                        window::get_minimized(id)
+                            .map(move |minimized| (mode, minimized))
+                    })
+                    .then(move |(mode, minimized)| {
+                        if mode == window::Mode::Hidden
+                            || minimized == Some(true)
+                        {
+                            show_window(id)
+                        } else {
+                            window::change_mode(id, window::Mode::Hidden)
+                        }
+                    });
+            }
+            Message::WindowResized(size) => {
+                // Minimizing on Windows shrinks the window to nothing.
+                if size.width <= 0.0 || size.height <= 0.0 {
+                    return Command::none();
+                }
+                self.state.window.size = Some((size.width, size.height));
+                return self.geometry_changed();
+            }
+            Message::WindowMoved(point) => {
+                let Some(id) = self.window_id else {
+                    return Command::none();
+                };
+                // Minimized windows are moved off screen on Windows.
+                return window::get_minimized(id).then(move |minimized| {
+                    if minimized == Some(true) {
+                        Command::none()
+                    } else {
+                        Command::done(Message::WindowPlaced(point))
+                    }
+                });
+            }
+            Message::WindowPlaced(point) => {
+                self.state.window.position = Some((point.x, point.y));
+                return self.geometry_changed();
+            }
+            Message::SaveGeometry => {
+                if self
+                    .geometry_changed
+                    .is_some_and(|t| t.elapsed() >= GEOMETRY_SAVE_DELAY)
+                {
+                    self.geometry_changed = None;
+                    return self.save_state();
+                }
+                return Command::none();
+            }
+            Message::Quit => {
+                if self.geometry_changed.take().is_some() {
+                    self.quit_after_saving = true;
+                    return self.save_state();
+                }
+                if self.pending_saves > 0 {
+                    info!(
+                        "Waiting for {} saves before quitting...",
+                        self.pending_saves
+                    );
+                    self.quit_after_saving = true;
+                    return Command::none();
+                }
+                info!("Quitting.");
+                return match self.window_id {
+                    Some(id) => window::close(id),
+                    None => iced::exit(),
+                };
+            }
+            Message::CancelQuit => {
+                self.quit_requested = false;
+                return Command::none();
+            }
+            Message::BulkSaved {
+                ids,
+                failed,
+                out_of_sync,
+            } => {
+                for id in ids {
+                    self.pending_entries.remove(&id);
+                }
+                if !failed.is_empty() {
+                    self.error = format!(
+                        "Failed to update {} entries: {}",
+                        failed.len(),
+                        failed
+                            .iter()
+                            .map(|(entry, e)| format!(
+                                "{} ({e})",
+                                entry.description.as_deref().unwrap_or("")
+                            ))
+                            .join("; ")
+                    );
+                }
+                for (entry, _) in failed {
+                    self.state.replace_entry(entry);
+                }
+                let save = self.save_state();
+                if out_of_sync {
+                    warn!("Bulk edit does not match the server, reloading.");
+                    return Command::batch(vec![
+                        save,
+                        Command::done(Message::Reload),
+                    ]);
+                }
+                return save;
+            }
             _ => {}
         };
 
@@ -219,13 +1375,57 @@ impl App {
             Screen::Loading => match message {
                 Message::Loaded(Ok(state)) => {
                     info!("Loaded state file.");
-                    self.screen = Screen::Authed;
                     let api_token = state.api_token.clone();
+                    Client::set_network_settings(
+                        state.customization.network_settings(),
+                    );
                     self.state = *state;
-                    return Command::future(Self::load_everything(api_token));
+                    // The entry may have been added or removed elsewhere.
+                    self.state.customization.start_on_login =
+                        autostart::is_enabled();
+                    self.apply_config();
+                    // The window id may have come before the state.
+                    let level = Command::batch(vec![
+                        self.apply_window_level(),
+                        self.restore_window_geometry(),
+                        self.apply_start_minimized(),
+                    ]);
+                    if self.state.has_cached_data() {
+                        // Show the cache right away, fresh data follows.
+                        debug!("Rendering cached data while refreshing.");
+                        self.screen = Screen::Loaded(TemporaryState::default());
+                        return Command::batch(vec![
+                            Command::future(Self::load_everything(api_token)),
+                            self.update_icon(),
+                            level,
+                        ]);
+                    }
+                    self.screen = Screen::Authed(None);
+                    return Command::batch(vec![
+                        Command::future(Self::load_everything(api_token)),
+                        level,
+                    ]);
+                }
+                Message::Loaded(Err(LoadError::Keyring(state))) => {
+                    warn!("No API token, logging in again.");
+                    // Keep the settings, cache and unsent changes.
+                    Client::set_network_settings(
+                        state.customization.network_settings(),
+                    );
+                    self.state = *state;
+                    // The entry may have been added or removed elsewhere.
+                    self.state.customization.start_on_login =
+                        autostart::is_enabled();
+                    self.apply_config();
+                    self.screen = Screen::Unauthed(LoginScreen::new());
+                    return Command::batch(vec![
+                        self.apply_window_level(),
+                        self.restore_window_geometry(),
+                    ]);
                 }
                 Message::Loaded(Err(e)) => {
                     error!("Failed to load state file: {e:?}");
+                    self.apply_config();
                     self.screen = Screen::Unauthed(LoginScreen::new());
                 }
                 _ => {}
@@ -235,15 +1435,13 @@ impl App {
                     api_token,
                 )) => {
                     info!("Authenticated successfully.");
-                    self.screen = Screen::Authed;
-                    self.state = State {
-                        api_token: api_token.clone(),
-                        ..State::default()
-                    };
-                    return Command::perform(self.state.clone().save(), |_| {
-                        Message::Discarded
-                    })
-                    .chain(Command::future(Self::load_everything(api_token)));
+                    self.screen = Screen::Authed(None);
+                    // Blank after logging out, or what was kept when the
+                    // keyring couldn't be read.
+                    self.state.api_token.clone_from(&api_token);
+                    return self.save_state().chain(Command::future(
+                        Self::load_everything(api_token),
+                    ));
                 }
                 Message::LoginProxy(msg) => {
                     return screen.update(msg).map(Message::LoginProxy)
@@ -255,46 +1453,224 @@ impl App {
                 }
                 _ => {}
             },
-            Screen::Authed => {}
+            Screen::Authed(_) => {}
             Screen::Loaded(temp_state) => match message {
+                Message::TimeEntryProxy(TimeEntryMessage::OpenContextMenu(
+                    id,
+                )) => {
+                    temp_state.context_menu = Some(id);
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::CloseContextMenu) => {
+                    temp_state.context_menu = None;
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::SetFlag(
+                    id,
+                    flag,
+                )) => {
+                    temp_state.context_menu = None;
+                    self.state.set_flag(id, flag);
+                    return self.save_state();
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::CopyDetails(id)) => {
+                    temp_state.context_menu = None;
+                    let Some(entry) =
+                        self.state.time_entries.iter().find(|e| e.id == id)
+                    else {
+                        return Command::none();
+                    };
+                    let project = self
+                        .state
+                        .projects
+                        .iter()
+                        .find(|p| Some(p.id) == entry.project_id);
+                    return iced::clipboard::write(entry.details(
+                        project.map(|p| p.name.as_str()),
+                        &self.state.customization,
+                    ));
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::OpenInToggl) => {
+                    temp_state.context_menu = None;
+                    if let Err(e) = open_in_os(TOGGL_TIMER_URL) {
+                        error!("Failed to open {TOGGL_TIMER_URL}: {e}");
+                        self.error = format!("Failed to open the browser: {e}");
+                    }
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::Copy(entry)) => {
+                    temp_state.context_menu = None;
+                    let token = self.state.api_token.clone();
+                    return Command::future(async move {
+                        let client = Client::from_api_token(&token);
+                        match entry.copy_entry(&client).await {
+                            Err(e) => {
+                                error!("Failed to copy an entry: {e}");
+                                Message::Error(e.to_string())
+                            }
+                            Ok(_) => Message::Reload,
+                        }
+                    });
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::Delete(entry)) => {
+                    temp_state.context_menu = None;
+                    let token = self.state.api_token.clone();
+                    return Command::future(async move {
+                        let client = Client::from_api_token(&token);
+                        match entry.clone().delete(&client).await {
+                            Err(e) => {
+                                error!("Failed to delete an entry: {e}");
+                                offline_or_error(
+                                    e,
+                                    PendingChange::Delete(*entry),
+                                )
+                            }
+                            Ok(()) => Message::Deleted(entry.id),
+                        }
+                    });
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::CycleFlag(id)) => {
+                    let flag =
+                        EntryFlag::next(self.state.flags.get(&id).copied());
+                    self.state.set_flag(id, flag);
+                    return self.save_state();
+                }
                 Message::TimeEntryProxy(TimeEntryMessage::Edit(i)) => {
                     if let Some(entry) =
                         self.state.time_entries.iter().find(|e| e.id == i)
                     {
-                        self.screen = Screen::EditEntry(EditTimeEntry::new(
-                            entry.clone(),
-                            &self.state.api_token,
-                            &self.state.customization,
-                            self.state.projects.clone(),
-                        ));
+                        return self.open_editor(entry.clone());
                     }
                 }
                 Message::TimeEntryProxy(TimeEntryMessage::EditRunning) => {
+                    if let Some(entry) = self.state.running_entry.clone() {
+                        return self.open_editor(entry);
+                    }
+                }
+                Message::TimeEntryProxy(
+                    TimeEntryMessage::NudgeRunningStart(direction),
+                ) => {
                     if let Some(entry) = &self.state.running_entry {
-                        self.screen = Screen::EditEntry(EditTimeEntry::new(
-                            entry.clone(),
-                            &self.state.api_token,
-                            &self.state.customization,
-                            self.state.projects.clone(),
-                        ));
+                        return self.move_running_start(
+                            entry.start
+                                + self.state.customization.nudge_step()
+                                    * direction,
+                        );
                     }
                 }
+                Message::TimeEntryProxy(
+                    TimeEntryMessage::StartedAgoEdited(text),
+                ) => {
+                    temp_state.running_started_ago = text;
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::SubmitStartedAgo) => {
+                    match parse_elapsed(&temp_state.running_started_ago) {
+                        Err(e) => return Command::done(Message::Error(e)),
+                        Ok(elapsed) => {
+                            temp_state.running_started_ago.clear();
+                            return self.move_running_start(
+                                chrono::Local::now() - elapsed,
+                            );
+                        }
+                    }
+                }
+                Message::TimeEntryProxy(
+                    TimeEntryMessage::SetRunningProject(project_id),
+                ) => {
+                    return self
+                        .update_running(|entry| entry.project_id = project_id);
+                }
+                Message::TimeEntryProxy(
+                    TimeEntryMessage::RunningTagsEdited(text),
+                ) => {
+                    temp_state.running_tags = Some(text);
+                }
+                Message::TimeEntryProxy(
+                    TimeEntryMessage::SubmitRunningTags,
+                ) => {
+                    let Some(text) = temp_state.running_tags.take() else {
+                        return Command::none();
+                    };
+                    let tags = split_tags(&text);
+                    return self.update_running(|entry| entry.set_tags(tags));
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::ToggleStopAt) => {
+                    if temp_state.stop_at.take().is_some() {
+                        return Command::none();
+                    }
+                    temp_state.stop_at = Some(
+                        self.state
+                            .customization
+                            .format_time(&chrono::Local::now()),
+                    );
+                    return text_input::focus("stop-at-input");
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::StopAtEdited(
+                    text,
+                )) => {
+                    temp_state.stop_at = Some(text);
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::SubmitStopAt) => {
+                    let (Some(text), Some(entry)) = (
+                        temp_state.stop_at.as_ref(),
+                        self.state.running_entry.clone(),
+                    ) else {
+                        return Command::none();
+                    };
+                    let stop = match self
+                        .state
+                        .customization
+                        .parse_time(text)
+                        .and_then(|time| {
+                            past_stop_time(
+                                time,
+                                entry.start,
+                                chrono::Local::now(),
+                            )
+                            .map_err(|e| e.to_string())
+                        }) {
+                        Ok(stop) => stop,
+                        Err(e) => return Command::done(Message::Error(e)),
+                    };
+                    temp_state.stop_at = None;
+                    return self.stop_running_at(entry, stop);
+                }
                 Message::TimeEntryProxy(TimeEntryMessage::StopRunning) => {
+                    if self.state.stop_unsent_running(chrono::Local::now()) {
+                        return self.save_unsent_stop();
+                    }
                     if let Some(entry) = self.state.running_entry.clone() {
                         info!("Stopping running entry {}...", entry.id);
                         let token = self.state.api_token.clone();
+                        let customization = self.state.customization.clone();
                         return Command::future(async move {
                             let client = Client::from_api_token(&token);
-                            match entry.stop(&client).await {
+                            match entry
+                                .stop_rounded(&client, &customization)
+                                .await
+                            {
+                                Err(e) if is_already_stopped(&e) => {
+                                    warn!(
+                                        "Entry {} already stopped.",
+                                        entry.id
+                                    );
+                                    Message::OutOfDate(e.to_string())
+                                }
                                 Err(e) => {
                                     error!(
                                         "Failed to stop a running entry: {e}"
                                     );
-                                    Message::Error(e.to_string())
+                                    let stop = customization.stop_to_save(
+                                        entry.start,
+                                        chrono::Local::now(),
+                                    );
+                                    offline_or_error(
+                                        e,
+                                        PendingChange::Update(
+                                            entry.stopped_at(stop),
+                                        ),
+                                    )
                                 }
                                 Ok(_) => {
                                     info!("Entry stopped.");
-                                    Message::Reload
+                                    Message::Stopped(entry.id)
                                 }
                             }
                         });
@@ -302,32 +1678,98 @@ impl App {
                         warn!("Requested to stop a nonexistent running entry.");
                     }
                 }
+                Message::TimeEntryProxy(TimeEntryMessage::Duplicate(e))
+                    if self.state.running_entry.is_some()
+                        && !self
+                            .state
+                            .customization
+                            .skip_replace_confirmation =>
+                {
+                    temp_state.pending_duplicate = Some(e);
+                }
+                Message::CancelDuplicate => temp_state.pending_duplicate = None,
+                Message::ConfirmDuplicate { remember } => {
+                    let Some(e) = temp_state.pending_duplicate.take() else {
+                        return Command::none();
+                    };
+                    if !remember {
+                        return self.duplicate(e);
+                    }
+                    self.state.customization.skip_replace_confirmation = true;
+                    return Command::batch(vec![
+                        self.save_state(),
+                        self.duplicate(e),
+                    ]);
+                }
                 Message::TimeEntryProxy(TimeEntryMessage::Duplicate(e)) => {
-                    let token = self.state.api_token.clone();
+                    return self.duplicate(e);
+                }
+                Message::TimeEntryProxy(TimeEntryMessage::ToggleGroup(id)) => {
+                    temp_state.toggle_group(id);
+                }
+                Message::ToggleTimer => {
+                    if self.state.running_entry.is_some() {
+                        return Command::done(Message::TimeEntryProxy(
+                            TimeEntryMessage::StopRunning,
+                        ));
+                    }
+                    // Starts from whatever is typed, usually nothing.
+                    return Command::done(Message::SubmitNewRunningEntry);
+                }
+                Message::DuplicateLatest => {
+                    let latest = self
+                        .state
+                        .running_entry
+                        .as_ref()
+                        .or_else(|| self.state.time_entries.first());
+                    if let Some(entry) = latest {
+                        return Command::done(Message::TimeEntryProxy(
+                            TimeEntryMessage::Duplicate(Box::new(
+                                entry.clone(),
+                            )),
+                        ));
+                    }
+                    debug!("Nothing to duplicate.");
+                }
+                Message::CustomizationProxy(
+                    CustomizationMessage::EditRules,
+                ) => {
+                    self.screen = Screen::Rules(RulesScreen::new(
+                        self.state.customization.rules.clone(),
+                        self.state.projects.clone(),
+                    ));
+                }
+                Message::CustomizationProxy(CustomizationMessage::Save) => {
+                    Client::set_network_settings(
+                        self.state.customization.network_settings(),
+                    );
+                    return Command::batch(vec![
+                        self.save_state(),
+                        // The total period may have moved further back.
+                        self.load_period_history(),
+                        self.apply_window_level(),
+                    ]);
+                }
+                Message::CustomizationProxy(
+                    CustomizationMessage::RequestStartOnLogin(enabled),
+                ) => {
                     return Command::future(async move {
-                        let client = Client::from_api_token(&token);
-                        let entry = CreateTimeEntry::new(
-                            e.description,
-                            e.workspace_id,
-                            e.project_id,
-                        );
-                        match entry.create(&client).await {
-                            Err(e) => {
-                                error!("Failed to duplicate an entry: {e}");
-                                Message::Error(e.to_string())
+                        match autostart::set_enabled(enabled) {
+                            Ok(()) => {
+                                info!("Start on login set to {enabled}.");
+                                Message::CustomizationProxy(
+                                    CustomizationMessage::SetStartOnLogin(
+                                        enabled,
+                                    ),
+                                )
                             }
-                            Ok(_) => {
-                                info!("Entry duplicated.");
-                                Message::Reload
+                            Err(e) => {
+                                error!("Failed to change autostart: {e}");
+                                Message::Error(e)
                             }
                         }
                     });
                 }
-                Message::CustomizationProxy(CustomizationMessage::Save) => {
-                    return Command::perform(self.state.clone().save(), |_| {
-                        Message::Discarded
-                    });
-                }
                 Message::CustomizationProxy(msg) => {
                     return self
                         .state
@@ -335,31 +1777,116 @@ impl App {
                         .update(msg)
                         .map(Message::CustomizationProxy);
                 }
-                Message::SetInitialRunningEntry(description) => {
-                    temp_state.new_running_entry_description = description;
+                Message::OpenSearch => {
+                    let Some(workspace_id) = self.state.default_workspace
+                    else {
+                        return Command::done(Message::Error(
+                            "No workspace selected!".to_string(),
+                        ));
+                    };
+                    self.screen = Screen::Search(SearchScreen::new(
+                        &self.state.api_token,
+                        workspace_id,
+                        &self.state.customization,
+                    ));
+                    return text_input::focus("search-input");
                 }
-                Message::SubmitNewRunningEntry => {
-                    let token = self.state.api_token.clone();
-                    let description =
-                        temp_state.new_running_entry_description.clone();
+                Message::OpenDataFolder => {
+                    let dir = State::data_dir();
+                    if let Err(e) = open_in_os(&dir) {
+                        error!("Failed to open {}: {e}", dir.display());
+                        self.error =
+                            format!("Failed to open {}: {e}", dir.display());
+                    }
+                }
+                Message::CopyDataFolderPath => {
+                    return iced::clipboard::write(
+                        State::data_dir().display().to_string(),
+                    );
+                }
+                Message::OpenImport => {
                     let Some(workspace_id) = self.state.default_workspace
                     else {
                         return Command::done(Message::Error(
                             "No workspace selected!".to_string(),
                         ));
                     };
-                    let project_id = self.state.default_project;
+                    self.screen = Screen::Import(ImportScreen::new(
+                        &self.state.api_token,
+                        workspace_id,
+                        self.state.projects.clone(),
+                    ));
+                }
+                Message::OpenSummary => {
+                    self.screen = Screen::Summary(SummaryScreen::new(
+                        &self.state.api_token,
+                        self.state.default_workspace,
+                        temp_state.hidden_projects.clone(),
+                        temp_state.filter.clone(),
+                    ));
+                }
+                Message::OpenReport => {
+                    self.screen = Screen::Report(ReportScreen::default());
+                }
+                Message::OpenExport => {
+                    self.screen = Screen::Export(ExportScreen::new(
+                        &self.state.time_entries,
+                        &self.state.projects,
+                        &self.state.customization,
+                    ));
+                }
+                Message::FocusNewEntryInput => {
+                    return text_input::focus(RUNNING_ENTRY_INPUT_ID);
+                }
+                Message::SetInitialRunningEntry(description) => {
+                    temp_state.new_running_entry_description = description;
+                }
+                Message::SetSkipDefaultTags(skip) => {
+                    temp_state.skip_default_tags = skip;
+                }
+                Message::NewEntryDurationEdited(text) => {
+                    temp_state.new_entry_duration = text;
+                    temp_state.new_entry_duration_error = None;
+                }
+                Message::SubmitNewRunningEntry => {
+                    // A typed duration creates a finished entry instead.
+                    let times =
+                        if temp_state.new_entry_duration.trim().is_empty() {
+                            None
+                        } else {
+                            match completed_times(
+                                &temp_state.new_entry_duration,
+                                chrono::Local::now(),
+                            ) {
+                                Ok(times) => Some(times),
+                                Err(e) => {
+                                    temp_state.new_entry_duration_error =
+                                        Some(e);
+                                    return Command::none();
+                                }
+                            }
+                        };
+                    let mut entry = match self.state.new_entry(
+                        &temp_state.new_running_entry_description,
+                        !temp_state.skip_default_tags,
+                    ) {
+                        Ok(entry) => entry,
+                        Err(e) => return Command::done(Message::Error(e)),
+                    };
+                    if let Some((start, duration)) = times {
+                        entry = entry.with_times(start, duration);
+                    }
+                    let token = self.state.api_token.clone();
+                    temp_state.skip_default_tags = false;
                     return Command::future(async move {
                         let client = Client::from_api_token(&token);
-                        let entry = CreateTimeEntry::new(
-                            Some(description),
-                            workspace_id,
-                            project_id,
-                        );
                         match entry.create(&client).await {
                             Err(e) => {
                                 error!("Failed to create a new entry: {e}");
-                                Message::Error(e.to_string())
+                                offline_or_error(
+                                    e,
+                                    PendingChange::Create(entry),
+                                )
                             }
                             Ok(_) => {
                                 info!("Entry created.");
@@ -369,20 +1896,42 @@ impl App {
                     });
                 }
                 Message::LoadMore => {
+                    if self.loading_more {
+                        return Command::none();
+                    }
+                    self.loading_more = true;
                     info!("Loading older entries...");
                     let token = self.state.api_token.clone();
                     let first_start =
                         self.state.time_entries.last().map(|e| e.start);
                     return Command::future(async move {
                         let client = Client::from_api_token(&token);
-                        match TimeEntry::load(first_start, &client).await {
-                            Ok(res) => Message::LoadedMore(res),
-                            Err(e) => Message::Error(e.to_string()),
-                        }
+                        Message::LoadedMore(
+                            TimeEntry::load(first_start, &client)
+                                .await
+                                .map_err(|e| e.to_string()),
+                        )
                     });
                 }
-                Message::LoadedMore(entries) => {
+                Message::ListScrolled(viewport) => {
+                    let remaining = viewport.content_bounds().height
+                        - viewport.bounds().height
+                        - viewport.absolute_offset().y;
+                    if self.state.has_more_entries
+                        && !self.loading_more
+                        && remaining < LOAD_MORE_DISTANCE
+                    {
+                        return Command::done(Message::LoadMore);
+                    }
+                }
+                Message::LoadedMore(Err(e)) => {
+                    error!("Failed to load older entries: {e}");
+                    self.loading_more = false;
+                    self.error = e;
+                }
+                Message::LoadedMore(Ok(entries)) => {
                     info!("Loaded older entries.");
+                    self.loading_more = false;
                     if entries.is_empty() {
                         debug!("No older entries.");
                         self.state.has_more_entries = false;
@@ -392,9 +1941,85 @@ impl App {
                             Some(e.workspace_id) == self.state.default_workspace
                         },
                     ));
-                    return Command::perform(self.state.clone().save(), |_| {
-                        Message::Discarded
+                    sort_entries(&mut self.state.time_entries);
+                    return Command::batch(vec![
+                        self.save_state(),
+                        self.load_period_history(),
+                    ]);
+                }
+                Message::WindowFocused => {
+                    let now = Instant::now();
+                    let recent = |t: Option<Instant>, interval| {
+                        t.is_some_and(|t| now.duration_since(t) < interval)
+                    };
+                    if recent(self.last_focus_refresh, FOCUS_DEBOUNCE) {
+                        return Command::none();
+                    }
+                    self.last_focus_refresh = Some(now);
+                    if recent(self.last_full_sync, FULL_SYNC_INTERVAL) {
+                        return Command::done(Message::RefreshRunning);
+                    }
+                    info!("Window refocused, syncing with remote...");
+                    // Unlike Reload, keep the typed description and filters.
+                    return Command::future(Self::load_everything(
+                        self.state.api_token.clone(),
+                    ));
+                }
+                Message::RefreshRunning => {
+                    let token = self.state.api_token.clone();
+                    return Command::future(async move {
+                        let client = Client::from_api_token(&token);
+                        match TimeEntry::current(&client).await {
+                            Ok(entry) => {
+                                Message::RunningRefreshed(entry.map(Box::new))
+                            }
+                            Err(e) => Message::Error(e.to_string()),
+                        }
+                    });
+                }
+                Message::RunningRefreshed(_)
+                    if !self.state.pending_changes.is_empty() =>
+                {
+                    // The server doesn't know the queued changes yet.
+                    return Command::none();
+                }
+                Message::RunningRefreshed(entry) => {
+                    // Like the list, only show entries of this workspace.
+                    let entry = entry.map(|e| *e).filter(|e| {
+                        self.state
+                            .default_workspace
+                            .is_none_or(|ws| e.workspace_id == ws)
                     });
+                    let previous_id =
+                        self.state.running_entry.as_ref().map(|e| e.id);
+                    let stopped_elsewhere = previous_id.is_some()
+                        && previous_id != entry.as_ref().map(|e| e.id);
+                    self.state.running_entry = entry;
+                    if stopped_elsewhere {
+                        // The old entry's stop time is only known to the
+                        // server, so fetch the full list to show it.
+                        return Command::done(Message::Reload);
+                    }
+                    return self.update_icon();
+                }
+                Message::OutOfDate(e) => {
+                    self.error = e;
+                    return Command::done(Message::Reload);
+                }
+                Message::Stopped(id) => {
+                    let reload = Command::done(Message::Reload);
+                    match self.state.customization.post_stop_action {
+                        PostStopAction::Nothing => return reload,
+                        PostStopAction::FocusNewEntry => {
+                            return reload.chain(Command::done(
+                                Message::FocusNewEntryInput,
+                            ));
+                        }
+                        PostStopAction::EditEntry => {
+                            self.edit_after_load = Some(id);
+                            return reload;
+                        }
+                    }
                 }
                 Message::Reload => {
                     info!("Syncing with remote...");
@@ -410,12 +2035,95 @@ impl App {
                         self.state.api_token.clone(),
                     ));
                 }
+                Message::FilterProject(project_id, visible) => {
+                    if visible {
+                        temp_state.hidden_projects.remove(&project_id);
+                    } else {
+                        temp_state.hidden_projects.insert(project_id);
+                    }
+                }
+                Message::FilterChanged(filter) => {
+                    temp_state.filter = filter;
+                }
+                Message::SelectEntry(id, selected) => {
+                    if selected {
+                        temp_state.selected_entries.insert(id);
+                    } else {
+                        temp_state.selected_entries.remove(&id);
+                    }
+                }
+                Message::ClearSelection => {
+                    temp_state.selected_entries.clear();
+                }
+                Message::BulkTagEdited(tag) => {
+                    temp_state.bulk_tag = tag;
+                }
+                Message::BulkTag { add } => {
+                    let tag = temp_state.bulk_tag.trim().to_string();
+                    if tag.is_empty() {
+                        return Command::none();
+                    }
+                    let selected = temp_state.selected_entries.clone();
+                    return self.bulk_update(&selected, |entry| {
+                        if add {
+                            entry.add_tag(&tag);
+                        } else {
+                            entry.remove_tag(&tag);
+                        }
+                    });
+                }
+                Message::BulkProject(project) => {
+                    let selected = temp_state.selected_entries.clone();
+                    let project: Option<Project> = project.into();
+                    let fits = |entry: &TimeEntry| {
+                        project.as_ref().is_none_or(|p| {
+                            p.fits_workspace(entry.workspace_id)
+                        })
+                    };
+                    let skipped = self
+                        .state
+                        .time_entries
+                        .iter()
+                        .filter(|e| selected.contains(&e.id) && !fits(e))
+                        .count();
+                    if skipped > 0 {
+                        warn!("Skipping {skipped} entries from another workspace.");
+                        self.error = format!(
+                            "Skipped {skipped} entries from another workspace."
+                        );
+                    }
+                    let project_id = project.as_ref().map(|p| p.id);
+                    return self.bulk_update(&selected, |entry| {
+                        if fits(entry) {
+                            entry.project_id = project_id;
+                        }
+                    });
+                }
+                Message::ShowAllProjects => temp_state.hidden_projects.clear(),
+                Message::ShowUnassigned => {
+                    temp_state.hidden_projects = self
+                        .state
+                        .projects
+                        .iter()
+                        .map(|p| Some(p.id))
+                        .collect();
+                    temp_state.selected_entries = self
+                        .state
+                        .time_entries
+                        .iter()
+                        .filter(|e| e.project_id.is_none())
+                        .map(|e| e.id)
+                        .collect();
+                }
+                Message::HideAllProjects => {
+                    temp_state.hidden_projects = std::iter::once(None)
+                        .chain(self.state.projects.iter().map(|p| Some(p.id)))
+                        .collect();
+                }
                 Message::SelectProject(project_id) => {
                     info!("Selected project: {project_id:?}");
                     self.state.default_project = project_id;
-                    return Command::perform(self.state.clone().save(), |_| {
-                        Message::Discarded
-                    });
+                    return self.save_state();
                 }
                 _ => {}
             },
@@ -423,13 +2131,55 @@ impl App {
                 Message::EditTimeEntryProxy(
                     EditTimeEntryMessage::Completed,
                 ) => {
-                    self.screen = Screen::Loading;
-                    return Command::perform(State::load(), Message::Loaded);
+                    self.state.set_notes(screen.entry_id(), screen.notes());
+                    return self.leave_editor();
+                }
+                Message::EditTimeEntryProxy(
+                    ref msg @ (EditTimeEntryMessage::Applied(ref entry)
+                    | EditTimeEntryMessage::OutOfSync(ref entry)),
+                ) => {
+                    self.state.set_notes(screen.entry_id(), screen.notes());
+                    self.state.replace_entry((**entry).clone());
+                    let forward = screen
+                        .update(msg.clone(), &self.state.customization)
+                        .map(Message::EditTimeEntryProxy);
+                    return Command::batch(vec![self.save_state(), forward]);
+                }
+                Message::EditTimeEntryProxy(EditTimeEntryMessage::Queued(
+                    change,
+                )) => {
+                    if let PendingChange::Delete(_) = *change {
+                        self.state.notes.remove(&screen.entry_id());
+                        self.state.flags.remove(&screen.entry_id());
+                    } else {
+                        self.state.set_notes(screen.entry_id(), screen.notes());
+                    }
+                    // Reloading would fail while offline.
+                    self.screen = Screen::Loaded(TemporaryState::default());
+                    return Command::done(Message::Queued(*change));
+                }
+                Message::EditTimeEntryProxy(EditTimeEntryMessage::Deleted) => {
+                    self.state.notes.remove(&screen.entry_id());
+                    self.state.flags.remove(&screen.entry_id());
+                    return self.leave_editor();
                 }
                 Message::EscPressed
                 | Message::EditTimeEntryProxy(EditTimeEntryMessage::Abort) => {
                     self.screen = Screen::Loaded(TemporaryState::default())
                 }
+                Message::EditTimeEntryProxy(
+                    EditTimeEntryMessage::ProjectCreated(project),
+                ) => {
+                    info!("Created project {}.", project.name);
+                    self.state.projects.push(project.clone());
+                    let forward = screen
+                        .update(
+                            EditTimeEntryMessage::ProjectCreated(project),
+                            &self.state.customization,
+                        )
+                        .map(Message::EditTimeEntryProxy);
+                    return Command::batch(vec![self.save_state(), forward]);
+                }
                 Message::EditTimeEntryProxy(msg) => {
                     return screen
                         .update(msg, &self.state.customization)
@@ -437,42 +2187,145 @@ impl App {
                 }
                 _ => {}
             },
+            Screen::Search(screen) => match message {
+                Message::SearchProxy(SearchMessage::Selected(entry)) => {
+                    return self.open_editor(*entry);
+                }
+                Message::EscPressed
+                | Message::SearchProxy(SearchMessage::Abort) => {
+                    self.screen = Screen::Loaded(TemporaryState::default())
+                }
+                Message::SearchProxy(msg) => {
+                    return screen
+                        .update(msg, &self.state.customization)
+                        .map(Message::SearchProxy)
+                }
+                _ => {}
+            },
+            Screen::Rules(screen) => match message {
+                Message::RulesProxy(RulesMessage::Save) => {
+                    self.state.customization.rules = screen.rules().to_vec();
+                    self.screen = Screen::Loaded(TemporaryState::default());
+                    return self.save_state();
+                }
+                Message::EscPressed
+                | Message::RulesProxy(RulesMessage::Abort) => {
+                    self.screen = Screen::Loaded(TemporaryState::default())
+                }
+                Message::RulesProxy(msg) => screen.update(msg),
+                _ => {}
+            },
+            Screen::Import(screen) => match message {
+                Message::EscPressed
+                | Message::ImportProxy(ImportMessage::Abort) => {
+                    self.screen = Screen::Loaded(TemporaryState::default());
+                    return Command::done(Message::Reload);
+                }
+                Message::ImportProxy(msg) => {
+                    return screen.update(msg).map(Message::ImportProxy)
+                }
+                _ => {}
+            },
+            Screen::Summary(screen) => match message {
+                Message::EscPressed
+                | Message::SummaryProxy(SummaryMessage::Abort) => {
+                    self.screen = Screen::Loaded(TemporaryState::default())
+                }
+                Message::SummaryProxy(msg) => {
+                    return screen.update(msg).map(Message::SummaryProxy)
+                }
+                _ => {}
+            },
+            Screen::Report(screen) => match message {
+                Message::EscPressed
+                | Message::ReportProxy(ReportMessage::Abort) => {
+                    self.screen = Screen::Loaded(TemporaryState::default())
+                }
+                Message::ReportProxy(msg) => {
+                    return screen.update(msg).map(Message::ReportProxy)
+                }
+                _ => {}
+            },
+            Screen::Export(screen) => match message {
+                Message::EscPressed
+                | Message::ExportProxy(ExportMessage::Abort) => {
+                    self.screen = Screen::Loaded(TemporaryState::default())
+                }
+                Message::ExportProxy(msg) => {
+                    return screen.update(msg).map(Message::ExportProxy)
+                }
+                _ => {}
+            },
         };
         Command::none()
     }
 
     fn view(&self) -> Element<Message> {
+        if self.quit_requested {
+            return quit_confirmation();
+        }
+        if let Some(since) = &self.idle_since {
+            return idle_confirmation(
+                self.state.customization.format_time(since),
+            );
+        }
         match &self.screen {
             Screen::Loading => loading_message(),
-            Screen::Authed => loading_message(),
+            Screen::Authed(None) => loading_message(),
+            Screen::Authed(Some((kind, error))) => load_failure(*kind, error),
             Screen::Unauthed(screen) => screen.view().map(Message::LoginProxy),
+            Screen::Loaded(temp_state)
+                if temp_state.pending_duplicate.is_some() =>
+            {
+                replace_running_confirmation()
+            }
             Screen::Loaded(temp_state) => {
                 let running_entry = match &self.state.running_entry {
                     None => running_entry_input(
                         &temp_state.new_running_entry_description,
+                        &temp_state.new_entry_duration,
+                        temp_state.new_entry_duration_error.as_deref(),
+                        (!self.state.customization.default_tags().is_empty())
+                            .then_some(!temp_state.skip_default_tags),
                     ),
-                    Some(entry) => {
-                        entry.view_running().map(Message::TimeEntryProxy)
-                    }
+                    Some(entry) => entry
+                        .view_running(
+                            &self.state.projects,
+                            &self.state.customization,
+                            &temp_state.running_started_ago,
+                            temp_state.stop_at.as_deref(),
+                            temp_state.running_tags.as_deref(),
+                        )
+                        .map(Message::TimeEntryProxy),
+                };
+                let content = if self.state.time_entries.is_empty()
+                    && self.state.running_entry.is_none()
+                {
+                    blank_state(self.state.has_more_entries)
+                } else {
+                    column(
+                        self.state
+                            .time_entries
+                            .iter()
+                            .filter(|e| temp_state.is_visible(e))
+                            .chunk_by(|e| e.start.date_naive())
+                            .into_iter()
+                            .map(|(start, tasks)| {
+                                self.day_group(start, tasks, temp_state)
+                            }),
+                    )
+                    .push(
+                        row![button("Load more")
+                            .on_press_maybe(if self.state.has_more_entries {
+                                Some(Message::LoadMore)
+                            } else {
+                                None
+                            })
+                            .style(button::secondary)]
+                        .padding([10, 10]),
+                    )
+                    .into()
                 };
-                let content = column(
-                    self.state
-                        .time_entries
-                        .iter()
-                        .chunk_by(|e| e.start.date_naive())
-                        .into_iter()
-                        .map(|(start, tasks)| self.day_group(start, tasks)),
-                )
-                .push(
-                    row![button("Load more")
-                        .on_press_maybe(if self.state.has_more_entries {
-                            Some(Message::LoadMore)
-                        } else {
-                            None
-                        })
-                        .style(button::secondary)]
-                    .padding([10, 10]),
-                );
                 let error_repr = if self.error.is_empty() {
                     None
                 } else {
@@ -480,21 +2333,54 @@ impl App {
                 };
 
                 container(
-                    column![
-                        self.menu(),
-                        running_entry,
-                        container(scrollable(content)).style(|_| {
-                            container::Style {
-                                border: iced::Border {
-                                    color: iced::color!(0x0000cd),
-                                    width: 0.5,
-                                    radius: 0.into(),
+                    column![self.menu(temp_state), running_entry,]
+                        .push_maybe(
+                            (!temp_state.selected_entries.is_empty()).then(
+                                || bulk_bar(temp_state, &self.state.projects),
+                            ),
+                        )
+                        .push(
+                            text_input(
+                                "Filter by description or tag",
+                                &temp_state.filter,
+                            )
+                            .on_input(Message::FilterChanged)
+                            .size(14),
+                        )
+                        .push(
+                            container(
+                                scrollable(content)
+                                    .height(Fill)
+                                    .on_scroll(Message::ListScrolled),
+                            )
+                            .style(|_| {
+                                container::Style {
+                                    border: iced::Border {
+                                        color: iced::color!(0x0000cd),
+                                        width: 0.5,
+                                        radius: 0.into(),
+                                    },
+                                    ..container::Style::default()
+                                }
+                            }),
+                        )
+                        .push_maybe(
+                            self.clock_warning
+                                .as_ref()
+                                .map(|w| text(w).style(text::danger)),
+                        )
+                        .push_maybe(
+                            (!self.state.pending_changes.is_empty()).then(
+                                || {
+                                    text(format!(
+                                        "{} offline changes waiting to be sent",
+                                        self.state.pending_changes.len()
+                                    ))
+                                    .size(12)
                                 },
-                                ..container::Style::default()
-                            }
-                        })
-                    ]
-                    .push_maybe(error_repr),
+                            ),
+                        )
+                        .push_maybe(error_repr),
                 )
                 .center_x(Fill)
                 .into()
@@ -502,10 +2388,37 @@ impl App {
             Screen::EditEntry(screen) => {
                 screen.view().map(Message::EditTimeEntryProxy)
             }
+            Screen::Search(screen) => screen
+                .view(
+                    &self.state.time_entries,
+                    &self.state.projects,
+                    &self.state.customization,
+                )
+                .map(Message::SearchProxy),
+            Screen::Summary(screen) => screen
+                .view(
+                    &self.state.time_entries,
+                    self.state.running_entry.as_ref(),
+                    self.state.has_more_entries,
+                    &self.state.customization,
+                )
+                .map(Message::SummaryProxy),
+            Screen::Report(screen) => screen
+                .view(
+                    &self.state.time_entries,
+                    self.state.running_entry.as_ref(),
+                    &self.state.projects,
+                    self.state.has_more_entries,
+                    &self.state.customization,
+                )
+                .map(Message::ReportProxy),
+            Screen::Rules(screen) => screen.view().map(Message::RulesProxy),
+            Screen::Import(screen) => screen.view().map(Message::ImportProxy),
+            Screen::Export(screen) => screen.view().map(Message::ExportProxy),
         }
     }
 
-    fn menu(&self) -> Element<Message> {
+    fn menu(&self, temp_state: &TemporaryState) -> Element<Message> {
         let selected_ws = self.state.default_workspace;
         let ws_menu = menu::Menu::new(
             self.state
@@ -554,12 +2467,70 @@ impl App {
         )
         .max_width(200.0);
 
-        menu::MenuBar::new(vec![
+        let filter_menu = menu::Menu::new(
+            [
+                menu::Item::new(menu_button(
+                    "Select all",
+                    Message::ShowAllProjects,
+                )),
+                menu::Item::new(menu_button(
+                    "Select none",
+                    Message::HideAllProjects,
+                )),
+                menu::Item::new(menu_button(
+                    "Only without project",
+                    Message::ShowUnassigned,
+                )),
+                toggle_item(
+                    "No project",
+                    !temp_state.hidden_projects.contains(&None),
+                    |v| Message::FilterProject(None, v),
+                ),
+            ]
+            .into_iter()
+            .chain(self.state.projects.iter().map(|p| {
+                let id = Some(p.id);
+                toggle_item(
+                    &p.name,
+                    !temp_state.hidden_projects.contains(&id),
+                    move |v| Message::FilterProject(id, v),
+                )
+            }))
+            .collect(),
+        )
+        .max_width(200.0);
+
+        let mut items = vec![
             menu::Item::with_menu(
                 menu_button("Info", Message::Discarded)
                     .width(iced::Length::Fixed(40f32)),
                 menu::Menu::new(vec![
                     menu::Item::new(menu_button("Reload", Message::Reload)),
+                    menu::Item::new(menu_button("Search", Message::OpenSearch)),
+                    menu::Item::new(menu_button(
+                        "Summary",
+                        Message::OpenSummary,
+                    )),
+                    menu::Item::new(menu_button(
+                        "Time by project",
+                        Message::OpenReport,
+                    )),
+                    menu::Item::new(menu_button(
+                        "Import CSV",
+                        Message::OpenImport,
+                    )),
+                    menu::Item::new(menu_button(
+                        "Export CSV",
+                        Message::OpenExport,
+                    )),
+                    menu::Item::new(menu_button(
+                        "Open data folder",
+                        Message::OpenDataFolder,
+                    )),
+                    menu::Item::new(menu_button(
+                        "Copy data folder path",
+                        Message::CopyDataFolderPath,
+                    )),
                     menu::Item::with_menu(
                         menu_button("Workspaces", Message::Discarded),
                         ws_menu,
@@ -568,29 +2539,112 @@ impl App {
                         menu_button("Projects", Message::Discarded),
                         project_menu,
                     ),
+                    menu::Item::with_menu(
+                        menu_button("Filter", Message::Discarded),
+                        filter_menu,
+                    ),
+                    menu::Item::new(menu_button("Quit", Message::RequestQuit)),
                 ])
                 .max_width(120.0),
             ),
             self.state.customization.view(&Message::CustomizationProxy),
-        ])
-        .into()
+            menu::Item::new(
+                container(text({
+                    let now = chrono::Local::now();
+                    let (label, start) =
+                        self.state.customization.total_period(now);
+                    let committed = total_between(
+                        &self.state.time_entries,
+                        None,
+                        start,
+                        now,
+                    );
+                    let running = total_between(
+                        [],
+                        self.state.running_entry.as_ref(),
+                        start,
+                        now,
+                    );
+                    // Until older pages arrive the total is not final.
+                    let partial = if self.state.covers_since(start) {
+                        ""
+                    } else {
+                        " (loading older entries\u{2026})"
+                    };
+                    format!(
+                        "{label}: {}{partial}",
+                        self.state
+                            .customization
+                            .format_total(&committed, &running)
+                    )
+                }))
+                .padding([4, 8]),
+            ),
+        ];
+        // Keep the timer reachable when the window is too small to show
+        // the running bar comfortably.
+        if let Some(running) = &self.state.running_entry {
+            items.push(menu::Item::new(
+                button(text(format!(
+                    "\u{25A0} {}",
+                    running.duration_string(&self.state.customization)
+                )))
+                .on_press(Message::TimeEntryProxy(
+                    TimeEntryMessage::StopRunning,
+                ))
+                .style(button::text)
+                .padding([4, 8]),
+            ));
+        }
+        menu::MenuBar::new(items).into()
     }
 
     fn day_group<'a>(
         &self,
         start: chrono::NaiveDate,
         tasks: impl Iterator<Item = &'a TimeEntry>,
+        temp_state: &TemporaryState,
     ) -> Element<'a, Message> {
+        let tasks: Vec<_> = tasks.collect();
+        let total = tasks
+            .iter()
+            .map(|e| self.state.customization.round(&e.duration()))
+            .fold(chrono::Duration::zero(), |acc, d| acc + d);
+        let now = chrono::Local::now();
+        // The running entry lives outside day groups, count it separately.
+        let running = start
+            .and_time(chrono::NaiveTime::MIN)
+            .and_local_timezone(chrono::Local)
+            .earliest()
+            .filter(|_| start == now.date_naive())
+            .map_or(chrono::Duration::zero(), |day_start| {
+                total_between(
+                    [],
+                    self.state.running_entry.as_ref(),
+                    day_start,
+                    now,
+                )
+            });
         column(
             std::iter::once(
                 container(
-                    text(self.state.customization.format_date(&start))
+                    row![
+                        text(self.state.customization.format_date(&start))
+                            .style(text::success)
+                            .width(Fill),
+                        text(
+                            self.state
+                                .customization
+                                .format_total(&total, &running)
+                        )
                         .style(text::success),
+                    ]
+                    .padding(Padding {
+                        left: 10f32,
+                        right: 10f32,
+                        ..Padding::default()
+                    }),
                 )
-                .padding(Padding {
-                    left: 10f32,
-                    ..Padding::default()
-                })
                 .style(|_| container::Style {
                     background: Some(iced::color!(0xc8c8c8).into()),
                     ..container::Style::default()
@@ -598,13 +2652,76 @@ impl App {
                 .width(iced::Length::Fill)
                 .into(),
             )
-            .chain(tasks.flat_map(|task| {
-                vec![
-                    task.view(&self.state.projects)
-                        .map(Message::TimeEntryProxy),
-                    horizontal_rule(0.5).into(),
-                ]
-            })),
+            .chain(group_similar(tasks).into_iter().flat_map(
+                |group| {
+                    let mut rows = vec![];
+                    let expanded =
+                        temp_state.expanded_groups.contains(&group[0].id);
+                    let grouped =
+                        self.state.customization.group_similar_entries
+                            && group.len() > 1;
+                    if grouped {
+                        rows.push(
+                            TimeEntry::view_group(
+                                &group,
+                                &self.state.projects,
+                                &self.state.customization,
+                                expanded,
+                            )
+                            .map(Message::TimeEntryProxy),
+                        );
+                        rows.push(horizontal_rule(0.5).into());
+                    }
+                    if !grouped || expanded {
+                        for task in group {
+                            let id = task.id;
+                            rows.push(
+                                container(
+                                    row![
+                                        checkbox(
+                                            "",
+                                            temp_state
+                                                .selected_entries
+                                                .contains(&id)
+                                        )
+                                        .on_toggle(move |v| {
+                                            Message::SelectEntry(id, v)
+                                        }),
+                                        text(if self.is_pending(id) {
+                                            "\u{2026}"
+                                        } else {
+                                            ""
+                                        })
+                                        .width(10),
+                                        task.view(
+                                            &self.state.projects,
+                                            &self.state.customization,
+                                            self.state.flags.get(&id).copied(),
+                                        )
+                                        .map(Message::TimeEntryProxy),
+                                    ]
+                                    .align_y(Center),
+                                )
+                                .padding(Padding {
+                                    left: if grouped { 20f32 } else { 0f32 },
+                                    ..Padding::default()
+                                })
+                                .into(),
+                            );
+                            if temp_state.context_menu == Some(id) {
+                                rows.push(
+                                    task.view_context_menu(
+                                        self.state.flags.get(&id).copied(),
+                                    )
+                                    .map(Message::TimeEntryProxy),
+                                );
+                            }
+                            rows.push(horizontal_rule(0.5).into());
+                        }
+                    }
+                    rows
+                },
+            )),
         )
         .into()
     }
@@ -614,7 +2731,9 @@ impl App {
         ExtendedMe::load(&client)
             .await
             .map(|m| Message::DataFetched(Ok(m)))
-            .unwrap_or_else(|e| Message::DataFetched(Err(e.to_string())))
+            .unwrap_or_else(|e| {
+                Message::DataFetched(Err((FailureKind::of(&e), e.to_string())))
+            })
     }
 
     fn subscription(&self) -> iced::Subscription<Message> {
@@ -622,27 +2741,51 @@ impl App {
             key::Named as NamedKey, on_key_press, Key, Modifiers,
         };
         iced::Subscription::batch(vec![
-            iced::time::every(std::time::Duration::from_secs(1))
-                .map(|_| Message::Tick),
-            on_key_press(|key, modifiers| {
-                let Key::Named(key) = key else {
-                    return None;
-                };
-                match (key, modifiers) {
-                    (NamedKey::Tab, _) => {
-                        if modifiers.bits() == 0 {
-                            Some(Message::TabPressed(false))
-                        } else if modifiers == Modifiers::SHIFT {
-                            Some(Message::TabPressed(true))
-                        } else {
-                            None
-                        }
-                    }
-                    (NamedKey::Escape, _) if modifiers.bits() == 0 => {
-                        Some(Message::EscPressed)
+            if self.state.customization.calm_running_timer {
+                let start = self.state.running_entry.as_ref().map(|e| e.start);
+                iced::Subscription::run_with_id(start, minute_ticks(start))
+            } else {
+                iced::Subscription::run(clock_ticks)
+            },
+            window::close_requests().map(Message::CloseRequested),
+            tray::subscription().map(Message::TrayProxy),
+            iced::event::listen_with(|event, _, _| match event {
+                iced::Event::Window(window::Event::Focused) => {
+                    Some(Message::WindowFocused)
+                }
+                iced::Event::Window(window::Event::Resized(size)) => {
+                    Some(Message::WindowResized(size))
+                }
+                iced::Event::Window(window::Event::Moved(point)) => {
+                    Some(Message::WindowMoved(point))
+                }
+                _ => None,
+            }),
+            on_key_press(|key, modifiers| match key.as_ref() {
+                Key::Named(NamedKey::Tab) => {
+                    if modifiers.bits() == 0 {
+                        Some(Message::TabPressed(false))
+                    } else if modifiers == Modifiers::SHIFT {
+                        Some(Message::TabPressed(true))
+                    } else {
+                        None
                     }
-                    _ => None,
                 }
+                Key::Named(NamedKey::Escape) if modifiers.bits() == 0 => {
+                    Some(Message::EscPressed)
+                }
+                Key::Character("d") if modifiers == Modifiers::COMMAND => {
+                    Some(Message::DuplicateLatest)
+                }
+                Key::Character("n") if modifiers == Modifiers::COMMAND => {
+                    Some(Message::FocusNewEntryInput)
+                }
+                Key::Named(NamedKey::Enter)
+                    if modifiers == Modifiers::COMMAND =>
+                {
+                    Some(Message::ToggleTimer)
+                }
+                _ => None,
             }),
         ])
     }
@@ -652,22 +2795,267 @@ fn loading_message<'a>() -> Element<'a, Message> {
     center(text("Loading...").width(Fill).align_x(Center).size(50)).into()
 }
 
-fn running_entry_input(description: &str) -> Element<'_, Message> {
-    row![
-        text_input("Create new entry...", description)
-            .id("running-entry-input")
-            .style(|_, _| text_input::Style {
-                background: iced::color!(0x161616).into(),
-                border: iced::Border::default(),
-                icon: Color::WHITE,
-                placeholder: iced::color!(0xd8d8d8),
-                value: Color::WHITE,
-                selection: Color::WHITE,
-            })
+fn load_failure(kind: FailureKind, error: &str) -> Element<'_, Message> {
+    let (hint, action, message) = match kind {
+        FailureKind::Network => (
+            "Could not reach Toggl. Check the connection or proxy settings.",
+            "Retry",
+            Message::RetryLoad,
+        ),
+        FailureKind::Auth => (
+            "Toggl rejected the saved credentials.",
+            "Log in again",
+            Message::LogOut,
+        ),
+        FailureKind::Parse => (
+            "The data could not be read.",
+            "Reset data",
+            Message::ResetData,
+        ),
+    };
+    center(
+        column![
+            text("Loading failed").size(24),
+            text(hint),
+            text(error).size(12).style(text::danger),
+            button(action).on_press(message).style(button::primary),
+        ]
+        // Blips are common, so retrying stays one click away.
+        .push_maybe((kind != FailureKind::Network).then(|| {
+            button("Retry")
+                .on_press(Message::RetryLoad)
+                .style(button::secondary)
+        }))
+        .push_maybe((kind != FailureKind::Auth).then(|| {
+            button("Log Out")
+                .on_press(Message::LogOut)
+                .style(button::secondary)
+        }))
+        .spacing(10)
+        .padding(40)
+        .align_x(Center),
+    )
+    .into()
+}
+
+fn blank_state<'a>(has_more_entries: bool) -> Element<'a, Message> {
+    column![
+        text("No time entries yet").size(24),
+        text("Type what you are working on above to start your first timer."),
+        button("Start tracking")
+            .on_press(Message::FocusNewEntryInput)
+            .style(button::primary),
+    ]
+    .push_maybe(has_more_entries.then(|| {
+        button("Look for older entries")
+            .on_press(Message::LoadMore)
+            .style(button::text)
+    }))
+    .spacing(10)
+    .padding(40)
+    .width(Fill)
+    .align_x(Center)
+    .into()
+}
+
+fn quit_confirmation<'a>() -> Element<'a, Message> {
+    center(
+        column![
+            text("A timer is still running.").size(20),
+            button("Stop the timer and quit")
+                .on_press(Message::StopAndQuit)
+                .style(button::danger)
+                .width(Fill),
+            button("Keep it running and quit")
+                .on_press(Message::Quit)
+                .style(button::primary)
+                .width(Fill),
+            button("Cancel")
+                .on_press(Message::CancelQuit)
+                .style(button::secondary)
+                .width(Fill),
+        ]
+        .spacing(10)
+        .align_x(Center)
+        .max_width(300),
+    )
+    .into()
+}
+
+fn idle_confirmation<'a>(since: String) -> Element<'a, Message> {
+    center(
+        column![
+            text(format!("You have been idle since {since}.")).size(20),
+            button("Discard the idle time")
+                .on_press(Message::DiscardIdle)
+                .style(button::danger)
+                .width(Fill),
+            button("Keep it")
+                .on_press(Message::KeepIdle)
+                .style(button::primary)
+                .width(Fill),
+        ]
+        .spacing(10)
+        .align_x(Center)
+        .max_width(300),
+    )
+    .into()
+}
+
+fn replace_running_confirmation<'a>() -> Element<'a, Message> {
+    center(
+        column![
+            text("This will stop your current timer.").size(20),
+            button("Start the new one")
+                .on_press(Message::ConfirmDuplicate { remember: false })
+                .style(button::primary)
+                .width(Fill),
+            button("Start it and don't ask again")
+                .on_press(Message::ConfirmDuplicate { remember: true })
+                .style(button::secondary)
+                .width(Fill),
+            button("Cancel")
+                .on_press(Message::CancelDuplicate)
+                .style(button::secondary)
+                .width(Fill),
+        ]
+        .spacing(10)
+        .align_x(Center)
+        .max_width(300),
+    )
+    .into()
+}
+
+const RUNNING_ENTRY_INPUT_ID: &str = "running-entry-input";
+
+/// `default_tags` is `None` when there are no default tags to offer,
+/// otherwise whether they will be applied to the new entry.
+fn running_entry_input<'a>(
+    description: &'a str,
+    duration: &'a str,
+    duration_error: Option<&'a str>,
+    default_tags: Option<bool>,
+) -> Element<'a, Message> {
+    let input_style =
+        |_: &iced::Theme, _: text_input::Status| text_input::Style {
+            background: iced::color!(0x161616).into(),
+            border: iced::Border::default(),
+            icon: Color::WHITE,
+            placeholder: iced::color!(0xd8d8d8),
+            value: Color::WHITE,
+            selection: Color::WHITE,
+        };
+    let input = row![
+        text_input("Create new entry... @project #tag", description)
+            .id(RUNNING_ENTRY_INPUT_ID)
+            .style(input_style)
             .on_input(Message::SetInitialRunningEntry)
             .on_submit(Message::SubmitNewRunningEntry),
-        button("Create").on_press(Message::SubmitNewRunningEntry)
+        text_input("1:30", duration)
+            .style(input_style)
+            .on_input(Message::NewEntryDurationEdited)
+            .on_submit(Message::SubmitNewRunningEntry)
+            .width(60),
+    ]
+    .push_maybe(default_tags.map(|apply| {
+        checkbox("Tags", apply)
+            .on_toggle(|v| Message::SetSkipDefaultTags(!v))
+            .size(14)
+            .text_size(14)
+    }))
+    .push(button("Create").on_press(Message::SubmitNewRunningEntry))
+    .align_y(Center)
+    .spacing(5);
+    column![input]
+        .push_maybe(
+            duration_error.map(|e| text(e).size(12).style(text::danger)),
+        )
+        .spacing(4)
+        .into()
+}
+
+/// Open a URL or a path with the default OS handler.
+fn open_in_os(target: impl AsRef<std::ffi::OsStr>) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command.arg(target).spawn().map(|_| ())
+}
+
+/// A tick right after every wall-clock second; `iced::time::every` drifts
+/// against it and makes the running duration skip a second now and then.
+fn clock_ticks() -> impl iced::futures::Stream<Item = Message> {
+    iced::futures::stream::unfold((), |()| async {
+        async_std::task::sleep(until_next_second(chrono::Local::now())).await;
+        Some((Message::Tick, ()))
+    })
+}
+
+/// Like [`clock_ticks`], but once a minute for the calm running timer, when
+/// the duration of an entry running since `start` shows another minute.
+fn minute_ticks(
+    start: Option<chrono::DateTime<chrono::Local>>,
+) -> impl iced::futures::Stream<Item = Message> {
+    iced::futures::stream::unfold((), move |()| async move {
+        let now = chrono::Local::now();
+        async_std::task::sleep(until_next_minute(now, start)).await;
+        Some((Message::Tick, ()))
+    })
+}
+
+/// Bring the window back from the tray or the taskbar.
+fn show_window(id: window::Id) -> Command<Message> {
+    Command::batch(vec![
+        window::change_mode(id, window::Mode::Windowed),
+        window::minimize(id, false),
+        window::gain_focus(id),
+    ])
+}
+
+fn bulk_bar<'a>(
+    temp_state: &'a TemporaryState,
+    projects: &[Project],
+) -> Element<'a, Message> {
+    let tag = (!temp_state.bulk_tag.trim().is_empty()).then_some(());
+    row![
+        text(format!("{} selected", temp_state.selected_entries.len()))
+            .size(14),
+        text_input("Tag", &temp_state.bulk_tag)
+            .on_input(Message::BulkTagEdited)
+            .on_submit(Message::BulkTag { add: true })
+            .size(14),
+        button(text("Add tag").size(14))
+            .on_press_maybe(tag.map(|_| Message::BulkTag { add: true })),
+        button(text("Remove tag").size(14))
+            .on_press_maybe(tag.map(|_| Message::BulkTag { add: false }))
+            .style(button::secondary),
+        pick_list(
+            std::iter::once(MaybeProject::None)
+                .chain(
+                    filter_projects(projects, "")
+                        .into_iter()
+                        .cloned()
+                        .map(MaybeProject::Some),
+                )
+                .collect::<Vec<_>>(),
+            None::<MaybeProject>,
+            Message::BulkProject,
+        )
+        .placeholder("Set project")
+        .text_size(14),
+        button(text("Clear").size(14))
+            .on_press(Message::ClearSelection)
+            .style(button::text),
     ]
+    .align_y(Center)
+    .spacing(5)
+    .padding([5, 10])
     .into()
 }
 
@@ -675,6 +3063,8 @@ fn running_entry_input(description: &str) -> Element<'_, Message> {
 enum LoadError {
     File,
     Format,
+    /// The token couldn't be read; the rest of the state is kept.
+    Keyring(Box<State>),
 }
 
 #[derive(Debug, Clone)]
@@ -685,17 +3075,18 @@ enum SaveError {
 }
 
 impl State {
-    fn path() -> std::path::PathBuf {
-        let mut path = if let Some(project_dirs) =
+    fn data_dir() -> std::path::PathBuf {
+        if let Some(project_dirs) =
             directories_next::ProjectDirs::from("rs", "Iced", "toggl-tracker")
         {
             project_dirs.data_dir().into()
         } else {
             std::env::current_dir().unwrap_or_default()
-        };
+        }
+    }
 
-        path.push("toggl.json");
-        path
+    fn path() -> std::path::PathBuf {
+        Self::data_dir().join("toggl.json")
     }
 
     async fn load() -> Result<Box<Self>, LoadError> {
@@ -711,12 +3102,111 @@ impl State {
             .await
             .map_err(|_| LoadError::File)?;
 
-        serde_json::from_str(&contents).map_err(|_| LoadError::Format)
+        let mut state = Self::from_json(&contents)?;
+        if state.token_in_keyring {
+            match secret::load(Secret::ApiToken) {
+                Ok(token) => state.api_token = token,
+                Err(e) => {
+                    error!("{e}");
+                    return Err(LoadError::Keyring(state));
+                }
+            }
+        }
+        if state.proxy_password_in_keyring {
+            match secret::load(Secret::ProxyPassword) {
+                Ok(password) => {
+                    state.customization.set_proxy_password(password);
+                }
+                Err(e) => warn!("{e}"),
+            }
+        }
+        // Before the keyring was used, the token was stored in plain text,
+        // and so was the password in the proxy URL.
+        let plaintext_token =
+            !state.token_in_keyring && !state.api_token.is_empty();
+        let plaintext_password = state.customization.take_proxy_url_password();
+        if state.version < STATE_VERSION
+            || plaintext_token
+            || plaintext_password
+        {
+            if let Err(e) = state.clone().write().await {
+                warn!("Failed to re-save the state file: {e:?}");
+            }
+        }
+        Ok(state)
+    }
+
+    fn from_json(contents: &str) -> Result<Box<Self>, LoadError> {
+        let value =
+            serde_json::from_str(contents).map_err(|_| LoadError::Format)?;
+        serde_json::from_value(migrate_state(value)).map_err(|e| {
+            error!("Failed to parse state file: {e}");
+            LoadError::Format
+        })
+    }
+
+    /// Move the token to the keyring, leaving it in the file if that fails.
+    fn secure_token(&mut self) {
+        // The keyring couldn't be read on load, keep the token it holds.
+        if self.token_in_keyring && self.api_token.is_empty() {
+            return;
+        }
+        let result = if self.api_token.is_empty() {
+            secret::delete(Secret::ApiToken)
+        } else {
+            secret::store(Secret::ApiToken, &self.api_token)
+        };
+        match result {
+            Ok(()) => {
+                self.token_in_keyring = !self.api_token.is_empty();
+                self.api_token.clear();
+            }
+            Err(e) => {
+                warn!("{e}, storing it in plain text.");
+                self.token_in_keyring = false;
+            }
+        }
+    }
+
+    /// Keep the proxy password in the keyring, or only in memory without one.
+    fn secure_proxy_password(&mut self) {
+        let password = self.customization.proxy_password();
+        // Nothing stored, or the keyring couldn't be read on load.
+        if password.is_empty()
+            && (!self.proxy_password_in_keyring
+                || !secret::is_known(Secret::ProxyPassword))
+        {
+            return;
+        }
+        let result = if password.is_empty() {
+            secret::delete(Secret::ProxyPassword)
+        } else {
+            secret::store(Secret::ProxyPassword, password)
+        };
+        match result {
+            Ok(()) => self.proxy_password_in_keyring = !password.is_empty(),
+            Err(e) => {
+                warn!("{e}, keeping it only until the app quits.");
+                self.proxy_password_in_keyring = false;
+            }
+        }
     }
 
     async fn save(self) -> Result<(), SaveError> {
+        self.write().await?;
+
+        // This is a simple way to save at most once every couple seconds
+        async_std::task::sleep(std::time::Duration::from_secs(2)).await;
+
+        Ok(())
+    }
+
+    async fn write(mut self) -> Result<(), SaveError> {
         use async_std::prelude::*;
 
+        self.secure_token();
+        self.secure_proxy_password();
+        self.version = STATE_VERSION;
         let json = serde_json::to_string_pretty(&self)
             .map_err(|_| SaveError::Format)?;
 
@@ -738,9 +3228,264 @@ impl State {
                 .map_err(|_| SaveError::Write)?;
         }
 
-        // This is a simple way to save at most once every couple seconds
-        async_std::task::sleep(std::time::Duration::from_secs(2)).await;
-
         Ok(())
     }
 }
+
+/// Queue `change` when `error` means the server was not reached.
+fn offline_or_error(error: surf::Error, change: PendingChange) -> Message {
+    if FailureKind::is_offline(&error) {
+        Message::Queued(change)
+    } else {
+        Message::Error(error.to_string())
+    }
+}
+
+/// Upgrade the JSON of an older state file to the current layout.
+///
+/// Files from newer versions are passed through: unknown fields are ignored.
+fn migrate_state(mut value: serde_json::Value) -> serde_json::Value {
+    let version = value
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0);
+    if version > STATE_VERSION {
+        warn!("State file version {version} is newer than this app.");
+    }
+    if version < 1 {
+        // Entries cached before tags were stored have no tag fields.
+        let fill_tags = |entry: &mut serde_json::Value| {
+            if let Some(entry) = entry.as_object_mut() {
+                for key in ["tags", "tag_ids"] {
+                    if entry.get(key).is_none_or(|v| v.is_null()) {
+                        entry.insert(key.to_string(), serde_json::json!([]));
+                    }
+                }
+            }
+        };
+        if let Some(entry) = value.get_mut("running_entry") {
+            fill_tags(entry);
+        }
+        if let Some(entries) = value
+            .get_mut("time_entries")
+            .and_then(serde_json::Value::as_array_mut)
+        {
+            entries.iter_mut().for_each(fill_tags);
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Local};
+
+    use super::{
+        App, Message, State, WindowGeometry, LOCAL_ENTRY_ID, MAX_PERIOD_PAGES,
+        STATE_VERSION,
+    };
+    use crate::time_entry::{test_entry, CreateTimeEntry, PendingChange};
+
+    fn entry_with_id(
+        id: u64,
+        hours_ago: i64,
+        running: bool,
+    ) -> super::TimeEntry {
+        let start = Local::now() - Duration::hours(hours_ago);
+        let mut entry = test_entry(
+            start,
+            (!running).then(|| start + Duration::minutes(30)),
+            None,
+        );
+        entry.id = id;
+        entry
+    }
+
+    fn create(running: bool) -> PendingChange {
+        let workspace_id = test_entry(Local::now(), None, None).workspace_id;
+        let entry = CreateTimeEntry::new(None, workspace_id, None, vec![]);
+        PendingChange::Create(if running {
+            entry
+        } else {
+            let start = Local::now() - Duration::hours(1);
+            entry.with_times(start, 600)
+        })
+    }
+
+    #[test]
+    fn test_apply_change() {
+        let mut state = State {
+            running_entry: Some(entry_with_id(1, 1, true)),
+            time_entries: vec![entry_with_id(2, 3, false)],
+            ..State::default()
+        };
+        let mut stopped = entry_with_id(1, 1, false);
+        stopped.description = Some("Stopped".to_string());
+        state.apply_change(&PendingChange::Update(stopped));
+        assert!(state.running_entry.is_none());
+        assert_eq!(state.time_entries[0].id, 1);
+        assert_eq!(
+            state.time_entries[0].description.as_deref(),
+            Some("Stopped")
+        );
+
+        state.apply_change(&PendingChange::Delete(entry_with_id(2, 3, false)));
+        assert_eq!(state.time_entries.len(), 1);
+
+        state.running_entry = Some(entry_with_id(3, 2, true));
+        state.apply_change(&PendingChange::Delete(entry_with_id(3, 2, true)));
+        assert!(state.running_entry.is_none());
+    }
+
+    #[test]
+    fn test_apply_create() {
+        let mut state = State {
+            running_entry: Some(entry_with_id(1, 1, true)),
+            ..State::default()
+        };
+        state.apply_change(&create(false));
+        assert_eq!(state.time_entries.len(), 1);
+        assert_eq!(state.time_entries[0].id, LOCAL_ENTRY_ID);
+
+        // Starting another entry stops the running one.
+        state.apply_change(&create(true));
+        let running = state.running_entry.as_ref().unwrap();
+        assert_eq!(running.id, LOCAL_ENTRY_ID);
+        assert!(running.is_running());
+        assert!(state
+            .time_entries
+            .iter()
+            .any(|e| e.id == 1 && !e.is_running()));
+    }
+
+    #[test]
+    fn test_stop_unsent_running() {
+        let mut state = State::default();
+        assert!(!state.stop_unsent_running(Local::now()));
+        let change = create(true);
+        state.apply_change(&change);
+        state.pending_changes.push(change);
+        assert!(state.stop_unsent_running(Local::now()));
+        assert!(state.running_entry.is_none());
+        assert_eq!(state.time_entries.len(), 1);
+        let [PendingChange::Create(created)] = &state.pending_changes[..]
+        else {
+            panic!("The creation should stay queued");
+        };
+        assert!(!created.is_running());
+    }
+
+    #[test]
+    fn test_pending_flushed() {
+        let changes: Vec<_> = (1..=3)
+            .map(|id| {
+                PendingChange::Update(entry_with_id(id, id as i64, false))
+            })
+            .collect();
+        let mut app = App::default();
+        app.state.pending_changes = changes;
+        app.flushing = true;
+        let _ = app.update(Message::PendingFlushed {
+            sent: 2,
+            errors: vec!["Rejected".to_string()],
+        });
+        assert!(!app.flushing);
+        // The sent changes are the oldest ones.
+        let left: Vec<_> = app
+            .state
+            .pending_changes
+            .iter()
+            .map(PendingChange::entry_id)
+            .collect();
+        assert_eq!(left, [3]);
+        assert!(app.error.contains("Rejected"), "{}", app.error);
+
+        // The queue may have been dropped meanwhile.
+        app.state.pending_changes.clear();
+        let _ = app.update(Message::PendingFlushed {
+            sent: 1,
+            errors: vec![],
+        });
+        assert!(app.state.pending_changes.is_empty());
+    }
+
+    #[test]
+    fn test_period_history_is_capped() {
+        let mut app = App::default();
+        app.state.has_more_entries = true;
+        for _ in 0..MAX_PERIOD_PAGES + 3 {
+            let _ = app.load_period_history();
+        }
+        assert_eq!(app.period_pages, MAX_PERIOD_PAGES);
+
+        app.state.has_more_entries = false;
+        let _ = app.load_period_history();
+        assert_eq!(app.period_pages, 0);
+    }
+
+    #[test]
+    fn test_migrate_v0_state() {
+        let v0 = serde_json::json!({
+            "api_token": "secret",
+            "time_entries": [{
+                "at": "2024-01-01T00:00:00Z",
+                "billable": false,
+                "description": "Old entry",
+                "duration": 60,
+                "id": 7,
+                "permissions": null,
+                "project_id": null,
+                "start": "2024-01-01T10:00:00Z",
+                "stop": "2024-01-01T10:01:00Z",
+                "server_deleted_at": null,
+                "task_id": null,
+                "user_id": 1,
+                "workspace_id": 1,
+            }],
+            "running_entry": null,
+            "has_more_entries": true,
+            "projects": [],
+            "workspaces": [],
+            "default_workspace": null,
+            "default_project": null,
+            "customization": {"default_tags": "work"},
+        });
+        let state = State::from_json(&v0.to_string()).unwrap();
+        assert_eq!(state.version, 0);
+        assert_eq!(state.api_token, "secret");
+        assert!(state.has_more_entries);
+        assert_eq!(state.time_entries.len(), 1);
+        assert_eq!(state.time_entries[0].id, 7);
+        assert!(state.time_entries[0].tags.is_empty());
+        assert_eq!(state.customization.default_tags(), vec!["work"]);
+
+        let mut saved = serde_json::to_value(&*state).unwrap();
+        saved["version"] = STATE_VERSION.into();
+        let reloaded = State::from_json(&saved.to_string()).unwrap();
+        assert_eq!(reloaded.version, STATE_VERSION);
+        assert_eq!(
+            reloaded.time_entries[0].description,
+            state.time_entries[0].description
+        );
+    }
+
+    #[test]
+    fn test_restorable_geometry() {
+        let minimized = WindowGeometry {
+            size: Some((0.0, 0.0)),
+            position: Some((-32000.0, -32000.0)),
+        };
+        assert_eq!(
+            minimized.restorable(),
+            WindowGeometry {
+                size: None,
+                position: Some((0.0, 0.0)),
+            }
+        );
+        let normal = WindowGeometry {
+            size: Some((500.0, 600.0)),
+            position: Some((120.0, 80.0)),
+        };
+        assert_eq!(normal.restorable(), normal);
+    }
+}
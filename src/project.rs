@@ -1,8 +1,19 @@
+use log::debug;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+use crate::client::{Client, Result as NetResult};
+use crate::workspace::WorkspaceId;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct ProjectId(u64);
 
+#[cfg(any(test, feature = "test-util"))]
+impl From<u64> for ProjectId {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
 impl std::fmt::Display for ProjectId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
@@ -15,11 +26,105 @@ pub struct Project {
     pub name: String,
     pub active: bool,
     pub color: String,
+    #[serde(default)]
+    pub workspace_id: Option<WorkspaceId>,
+    #[serde(default)]
+    pub client_id: Option<u64>,
+    /// Filled in from the client list, the API only sends the id.
+    #[serde(default)]
+    pub client_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProjectClient {
+    pub id: u64,
+    pub name: String,
+}
+
+/// Copy the client names onto the projects that reference them.
+pub fn fill_client_names(projects: &mut [Project], clients: &[ProjectClient]) {
+    for project in projects {
+        project.client_name = project.client_id.and_then(|id| {
+            clients.iter().find(|c| c.id == id).map(|c| c.name.clone())
+        });
+    }
 }
 
+/// Color the Toggl web app suggests for new projects.
+pub const DEFAULT_PROJECT_COLOR: &str = "#06aaf5";
+
+/// Check a new project's name and color, returning the trimmed values.
+pub fn validate_new_project(
+    name: &str,
+    color: &str,
+) -> Result<(String, String), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Project name can't be empty.".to_string());
+    }
+    let color = color.trim().to_lowercase();
+    let valid_color = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid_color {
+        return Err(format!("Invalid color '{color}', use #rrggbb."));
+    }
+    Ok((name.to_string(), color))
+}
+
+impl Project {
+    pub async fn create(
+        workspace_id: WorkspaceId,
+        name: &str,
+        color: &str,
+        client: &Client,
+    ) -> NetResult<Self> {
+        #[derive(Serialize)]
+        struct CreateProject<'a> {
+            name: &'a str,
+            color: &'a str,
+            active: bool,
+        }
+
+        debug!("Creating project {name} in workspace {workspace_id}...");
+        let mut res = client
+            .post(
+                [
+                    Client::BASE_URL,
+                    &format!("/api/v9/workspaces/{workspace_id}/projects"),
+                ]
+                .join(""),
+            )
+            .body_json(&CreateProject {
+                name,
+                color,
+                active: true,
+            })?
+            .send()
+            .await?;
+        if res.status() == surf::StatusCode::Forbidden {
+            return Err(surf::Error::from_str(
+                res.status(),
+                "You are not allowed to create projects in this workspace.",
+            ));
+        }
+        Client::check_status(&mut res).await?;
+        res.body_json().await
+    }
+
+    /// Whether entries of `workspace_id` can be assigned to this project.
+    pub fn fits_workspace(&self, workspace_id: WorkspaceId) -> bool {
+        self.workspace_id.is_none_or(|ws| ws == workspace_id)
+    }
+}
+
+/// `Client / Project`, or just the name for projects without a client.
 impl std::fmt::Display for Project {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.name)
+        match &self.client_name {
+            Some(client) => write!(f, "{client} / {}", self.name),
+            None => f.write_str(&self.name),
+        }
     }
 }
 
@@ -61,3 +166,140 @@ impl From<MaybeProject> for Option<Project> {
         }
     }
 }
+
+/// Projects whose name or client contains `query` (case-insensitive).
+///
+/// The ones where either starts with it come first, so the top match is the
+/// best one; each part is grouped by client.
+pub fn filter_projects<'a>(
+    projects: &'a [Project],
+    query: &str,
+) -> Vec<&'a Project> {
+    let query = query.trim().to_lowercase();
+    let mut matches: Vec<_> = projects
+        .iter()
+        .filter_map(|p| {
+            std::iter::once(&p.name)
+                .chain(&p.client_name)
+                .filter_map(|text| text.to_lowercase().find(&query))
+                .min()
+                .map(|pos| (pos != 0, p))
+        })
+        .collect();
+    // Stable, so the original order is kept within each group.
+    matches.sort_by(|(a_not_prefix, a), (b_not_prefix, b)| {
+        a_not_prefix
+            .cmp(b_not_prefix)
+            .then_with(|| a.client_name.cmp(&b.client_name))
+    });
+    matches.into_iter().map(|(_, p)| p).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        fill_client_names, filter_projects, validate_new_project, Project,
+        ProjectClient, ProjectId,
+    };
+
+    fn project(id: u64, name: &str) -> Project {
+        Project {
+            id: ProjectId(id),
+            name: name.to_string(),
+            active: true,
+            color: "#000000".to_string(),
+            workspace_id: None,
+            client_id: None,
+            client_name: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_new_project() {
+        assert_eq!(
+            validate_new_project("  Docs ", "#06AAF5"),
+            Ok(("Docs".to_string(), "#06aaf5".to_string()))
+        );
+        assert!(validate_new_project(" ", "#06aaf5").is_err());
+        for color in ["06aaf5", "#06aaf", "#06aaf5f", "#06aafz", "#06aa\u{e9}"]
+        {
+            assert!(validate_new_project("Docs", color).is_err(), "{color}");
+        }
+    }
+
+    #[test]
+    fn test_fill_client_names() {
+        let mut projects =
+            vec![project(1, "A"), project(2, "B"), project(3, "C")];
+        projects[0].client_id = Some(10);
+        projects[1].client_id = Some(11);
+        fill_client_names(
+            &mut projects,
+            &[ProjectClient {
+                id: 10,
+                name: "Acme".to_string(),
+            }],
+        );
+        assert_eq!(projects[0].client_name.as_deref(), Some("Acme"));
+        assert_eq!(projects[1].client_name, None);
+        assert_eq!(projects[2].client_name, None);
+    }
+
+    #[test]
+    fn test_fits_workspace() {
+        let mut p = project(1, "Any");
+        assert!(p.fits_workspace(1.into()));
+        p.workspace_id = Some(2.into());
+        assert!(!p.fits_workspace(1.into()));
+        assert!(p.fits_workspace(2.into()));
+    }
+
+    #[test]
+    fn test_filter_projects() {
+        let projects = [
+            project(1, "Internal meetings"),
+            project(2, "Website"),
+            project(3, "Meetings"),
+            project(4, "Mobile app"),
+        ];
+        let names = |query| {
+            filter_projects(&projects, query)
+                .into_iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("meet"), ["Meetings", "Internal meetings"]);
+        assert_eq!(names(" WEB "), ["Website"]);
+        assert_eq!(names("xyz"), Vec::<&str>::new());
+
+        let mut projects = projects.to_vec();
+        projects[0].client_name = Some("Acme".to_string());
+        projects[1].client_name = Some("Acme".to_string());
+        let names = |query| {
+            filter_projects(&projects, query)
+                .into_iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(""),
+            [
+                "Meetings",
+                "Mobile app",
+                "Acme / Internal meetings",
+                "Acme / Website"
+            ]
+        );
+        assert_eq!(names("meet"), ["Meetings", "Acme / Internal meetings"]);
+        assert_eq!(
+            names("acme"),
+            ["Acme / Internal meetings", "Acme / Website"]
+        );
+        assert_eq!(names("").len(), 4);
+
+        // The top match starts with the query even if its client sorts later.
+        let projects = [project(5, "Internal web"), projects[1].clone()];
+        let top = filter_projects(&projects, "web")[0];
+        assert_eq!(top.to_string(), "Acme / Website");
+    }
+}
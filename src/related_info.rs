@@ -1,10 +1,11 @@
 use crate::{
     client::{Client, Result as NetResult},
-    project::Project,
+    project::{fill_client_names, Project, ProjectClient},
     time_entry::TimeEntry,
     workspace::Workspace,
 };
-use log::debug;
+use chrono::{DateTime, Duration, Local};
+use log::{debug, warn};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -13,6 +14,12 @@ pub struct ExtendedMe {
     pub projects: Vec<Project>,
     pub workspaces: Vec<Workspace>,
     pub time_entries: Vec<TimeEntry>,
+    /// `null` when the user has no clients.
+    #[serde(default)]
+    pub clients: Option<Vec<ProjectClient>>,
+    /// Server time taken from the response `Date` header.
+    #[serde(skip)]
+    pub server_time: Option<DateTime<Local>>,
 }
 
 impl ExtendedMe {
@@ -26,6 +33,60 @@ impl ExtendedMe {
             .send()
             .await?;
         Client::check_status(&mut rsp).await?;
-        rsp.body_json().await
+        let server_time = rsp.header("Date").and_then(|date| {
+            DateTime::parse_from_rfc2822(date.as_str())
+                .map_err(|e| warn!("Unparseable Date header {date}: {e}"))
+                .ok()
+                .map(|d| d.with_timezone(&Local))
+        });
+        let mut me: Self = rsp.body_json().await?;
+        me.server_time = server_time;
+        if let Some(clients) = &me.clients {
+            fill_client_names(&mut me.projects, clients);
+        }
+        Ok(me)
+    }
+}
+
+/// Largest difference from the server clock that goes unreported.
+const MAX_CLOCK_SKEW: Duration = Duration::minutes(3);
+
+/// Warning to show if the local clock is too far off the server one.
+pub fn clock_skew_warning(
+    server_time: DateTime<Local>,
+    local_time: DateTime<Local>,
+) -> Option<String> {
+    let skew = local_time - server_time;
+    if skew.abs() <= MAX_CLOCK_SKEW {
+        return None;
+    }
+    let direction = if skew > Duration::zero() {
+        "ahead of"
+    } else {
+        "behind"
+    };
+    Some(format!(
+        "Your system clock is {} minutes {direction} the server time. \
+         Durations and new entries will be wrong until it is fixed.",
+        skew.num_minutes().abs()
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Local};
+
+    use super::clock_skew_warning;
+
+    #[test]
+    fn test_clock_skew_warning() {
+        let now = Local::now();
+        assert_eq!(clock_skew_warning(now, now + Duration::seconds(90)), None);
+        let warning = clock_skew_warning(now, now - Duration::minutes(10))
+            .expect("Must warn");
+        assert!(warning.contains("10 minutes behind"));
+        let warning = clock_skew_warning(now, now + Duration::hours(1))
+            .expect("Must warn");
+        assert!(warning.contains("60 minutes ahead of"));
     }
 }
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Local, NaiveTime};
+use iced::widget::{
+    button, column, container, horizontal_rule, row, scrollable, text,
+};
+use iced::{Element, Fill, Right, Task as Command};
+
+use crate::customization::Customization;
+use crate::project::{Project, ProjectId};
+use crate::time_entry::{to_start_of_week, total_between, TimeEntry};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ReportRange {
+    #[default]
+    Week,
+    Today,
+}
+
+impl std::fmt::Display for ReportRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            ReportRange::Week => "This week",
+            ReportRange::Today => "Today",
+        };
+        f.write_str(repr)
+    }
+}
+
+impl ReportRange {
+    pub const VALUES: [Self; 2] = [Self::Week, Self::Today];
+
+    pub fn start(self, now: DateTime<Local>) -> DateTime<Local> {
+        match self {
+            ReportRange::Week => to_start_of_week(now),
+            ReportRange::Today => now
+                .date_naive()
+                .and_time(NaiveTime::MIN)
+                .and_local_timezone(Local)
+                .earliest()
+                .unwrap_or(now),
+        }
+    }
+}
+
+/// Time tracked per project between `start` and `end`, longest first.
+pub fn project_totals(
+    entries: &[TimeEntry],
+    running_entry: Option<&TimeEntry>,
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+) -> Vec<(Option<ProjectId>, Duration)> {
+    let mut totals: HashMap<Option<ProjectId>, Duration> = HashMap::new();
+    for entry in entries.iter().chain(running_entry) {
+        let tracked = total_between([entry], None, start, end);
+        if !tracked.is_zero() {
+            *totals
+                .entry(entry.project_id)
+                .or_insert_with(Duration::zero) += tracked;
+        }
+    }
+    let mut totals: Vec<_> = totals.into_iter().collect();
+    totals.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+    totals
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReportScreen {
+    range: ReportRange,
+}
+
+#[derive(Clone, Debug)]
+pub enum ReportMessage {
+    SelectRange(ReportRange),
+    Abort,
+}
+
+impl ReportScreen {
+    pub fn view<'a>(
+        &'a self,
+        entries: &'a [TimeEntry],
+        running_entry: Option<&'a TimeEntry>,
+        projects: &'a [Project],
+        has_more_entries: bool,
+        customization: &'a Customization,
+    ) -> Element<'a, ReportMessage> {
+        let now = Local::now();
+        let start = self.range.start(now);
+        let totals = project_totals(entries, running_entry, start, now);
+        let grand_total = totals
+            .iter()
+            .fold(Duration::zero(), |acc, (_, total)| acc + *total);
+        let oldest_loaded = entries.last().map(|e| e.start);
+        let incomplete =
+            has_more_entries && oldest_loaded.is_none_or(|s| s > start);
+
+        let ranges = row(ReportRange::VALUES.iter().map(|range| {
+            button(text(range.to_string()))
+                .on_press_maybe(
+                    (*range != self.range)
+                        .then_some(ReportMessage::SelectRange(*range)),
+                )
+                .style(button::secondary)
+                .into()
+        }))
+        .spacing(5);
+        let content = column![
+            column![button("X")
+                .on_press(ReportMessage::Abort)
+                .style(button::text),]
+            .align_x(Right)
+            .width(Fill),
+            row![text("Time by project").size(18).width(Fill)].push(ranges),
+            horizontal_rule(0.5),
+        ]
+        .extend(totals.into_iter().map(|(project_id, total)| {
+            let name = projects
+                .iter()
+                .find(|p| Some(p.id) == project_id)
+                .map_or("No project".to_string(), |p| p.name.clone());
+            report_row(name, customization.format_duration(&total))
+        }))
+        .push(horizontal_rule(0.5))
+        .push(report_row(
+            "Total".to_string(),
+            customization.format_duration(&grand_total),
+        ))
+        .push_maybe(incomplete.then(|| {
+            text(
+                "Older entries are not fully loaded yet: use \"Load more\" in \
+                 the entry list for exact totals.",
+            )
+            .style(text::danger)
+        }))
+        .spacing(10);
+
+        scrollable(container(content).center_x(Fill).padding(40)).into()
+    }
+
+    pub fn update(&mut self, message: ReportMessage) -> Command<ReportMessage> {
+        match message {
+            ReportMessage::SelectRange(range) => self.range = range,
+            ReportMessage::Abort => {}
+        }
+        Command::none()
+    }
+}
+
+fn report_row<'a>(label: String, value: String) -> Element<'a, ReportMessage> {
+    row![text(label).width(Fill), text(value)]
+        .spacing(10)
+        .into()
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Local, TimeZone};
+
+    use super::{project_totals, ReportRange};
+    use crate::time_entry::{test_entry, TimeEntry};
+
+    fn entry(
+        start: chrono::DateTime<Local>,
+        minutes: i64,
+        project_id: Option<u64>,
+    ) -> TimeEntry {
+        test_entry(start, Some(start + Duration::minutes(minutes)), project_id)
+    }
+
+    #[test]
+    fn test_project_totals() {
+        let now = Local.with_ymd_and_hms(2024, 6, 5, 12, 0, 0).unwrap();
+        let today = ReportRange::Today.start(now);
+        assert_eq!(today, Local.with_ymd_and_hms(2024, 6, 5, 0, 0, 0).unwrap());
+        let entries = [
+            entry(now - Duration::hours(3), 60, Some(1)),
+            entry(now - Duration::hours(2), 30, None),
+            entry(now - Duration::hours(1), 30, Some(1)),
+            // Crosses midnight: only the part after it counts.
+            entry(today - Duration::minutes(10), 20, Some(2)),
+            entry(now - Duration::days(1), 60, Some(2)),
+        ];
+        let minutes = |start| {
+            project_totals(&entries, None, start, now)
+                .into_iter()
+                .map(|(p, total)| {
+                    (p.map(|p| p.to_string()), total.num_minutes())
+                })
+                .collect::<Vec<_>>()
+        };
+        let project = |id: &str| Some(id.to_string());
+        assert_eq!(
+            minutes(today),
+            vec![(project("1"), 90), (None, 30), (project("2"), 10)]
+        );
+        assert_eq!(
+            minutes(ReportRange::Week.start(now)),
+            vec![(project("1"), 90), (project("2"), 80), (None, 30)]
+        );
+    }
+}
@@ -0,0 +1,343 @@
+#[cfg(feature = "gui")]
+use iced::widget::{
+    button, checkbox, column, container, horizontal_rule, pick_list, row,
+    scrollable, text, text_input,
+};
+#[cfg(feature = "gui")]
+use iced::{Center, Element, Fill, Length, Right};
+use serde::{Deserialize, Serialize};
+
+use crate::customization::{merge_tags, split_tags};
+#[cfg(feature = "gui")]
+use crate::project::MaybeProject;
+use crate::project::{Project, ProjectId};
+use crate::workspace::WorkspaceId;
+
+/// Fill in project and tags of entries whose description contains a text.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rule {
+    /// Text the description must contain, case-insensitive.
+    pub pattern: String,
+    pub project_id: Option<ProjectId>,
+    /// Comma-separated tags to add.
+    pub tags: String,
+    /// Replace an already chosen project instead of only filling an empty one.
+    pub overwrite: bool,
+}
+
+impl Rule {
+    fn matches(&self, description: &str) -> bool {
+        let pattern = self.pattern.trim().to_lowercase();
+        !pattern.is_empty() && description.to_lowercase().contains(&pattern)
+    }
+}
+
+/// Project and tags for an entry with `description` after applying `rules`.
+///
+/// The first matching rule with a project decides the project, tags of all
+/// matching rules are added to `tags`.
+pub fn apply_rules(
+    rules: &[Rule],
+    description: &str,
+    project_id: Option<ProjectId>,
+    tags: &[String],
+) -> (Option<ProjectId>, Vec<String>) {
+    let matching: Vec<_> =
+        rules.iter().filter(|r| r.matches(description)).collect();
+    let project_id = match matching.iter().find(|r| r.project_id.is_some()) {
+        Some(rule) if project_id.is_none() || rule.overwrite => rule.project_id,
+        _ => project_id,
+    };
+    let tags = matching.iter().fold(tags.to_vec(), |acc, r| {
+        merge_tags(&acc, &split_tags(&r.tags))
+    });
+    (project_id, tags)
+}
+
+/// Running entry typed as `Design work @ClientX #billable`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct QuickAdd {
+    pub description: String,
+    pub project_id: Option<ProjectId>,
+    pub tags: Vec<String>,
+}
+
+/// Split `@project` and `#tag` tokens out of a quick-add line.
+///
+/// Projects are matched by name ignoring case and spaces. Tags take the
+/// spelling of a matching `known_tags` entry, other tags are created as typed.
+/// Tokens that don't match a project, numbers like `#123` and a lone `@` or
+/// `#` stay in the description, which is kept as typed otherwise.
+pub fn parse_quick_add(
+    text: &str,
+    projects: &[Project],
+    workspace_id: WorkspaceId,
+    known_tags: &[String],
+) -> Result<QuickAdd, String> {
+    let simplify = |s: &str| s.replace(' ', "").to_lowercase();
+    let mut kept = String::new();
+    let mut matched = false;
+    let mut result = QuickAdd::default();
+    // Each chunk keeps its trailing whitespace, so that dropping a token
+    // leaves the spacing around the other words alone.
+    for chunk in text.split_inclusive(char::is_whitespace) {
+        let word = chunk.trim_end();
+        let project = word
+            .strip_prefix('@')
+            .filter(|n| !n.is_empty())
+            .and_then(|name| {
+                projects
+                    .iter()
+                    .filter(|p| p.fits_workspace(workspace_id))
+                    .find(|p| simplify(&p.name) == simplify(name))
+            });
+        let tag = word.strip_prefix('#').filter(|t| {
+            !t.is_empty() && !t.chars().all(|c| c.is_ascii_digit())
+        });
+        if let Some(project) = project {
+            if result.project_id.is_some() {
+                return Err(format!("Only one project allowed, got {word}"));
+            }
+            result.project_id = Some(project.id);
+        } else if let Some(tag) = tag {
+            let tag = known_tags
+                .iter()
+                .find(|t| t.eq_ignore_ascii_case(tag))
+                .map_or(tag, |t| t);
+            result.tags = merge_tags(&result.tags, &[tag.to_string()]);
+        } else {
+            kept.push_str(chunk);
+            continue;
+        }
+        matched = true;
+    }
+    result.description = if matched {
+        kept.trim().to_string()
+    } else {
+        text.to_string()
+    };
+    Ok(result)
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Debug)]
+pub struct RulesScreen {
+    rules: Vec<Rule>,
+    projects: Vec<Project>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Clone, Debug)]
+pub enum RulesMessage {
+    PatternEdited(usize, String),
+    ProjectSelected(usize, MaybeProject),
+    TagsEdited(usize, String),
+    OverwriteToggled(usize, bool),
+    Add,
+    Remove(usize),
+    Save,
+    Abort,
+}
+
+#[cfg(feature = "gui")]
+impl RulesScreen {
+    pub fn new(rules: Vec<Rule>, projects: Vec<Project>) -> Self {
+        Self { rules, projects }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn view(&self) -> Element<RulesMessage> {
+        let project_options: Vec<_> = std::iter::once(MaybeProject::None)
+            .chain(self.projects.iter().cloned().map(MaybeProject::from))
+            .collect();
+        let content = column![
+            column![button("X")
+                .on_press(RulesMessage::Abort)
+                .style(button::text),]
+            .align_x(Right)
+            .width(Fill),
+            text("Categorization rules").size(18),
+            text(
+                "When a description contains the text, the project is set \
+                 (if empty, unless overwriting) and the tags are added."
+            )
+            .size(12),
+            horizontal_rule(0.5),
+        ]
+        .extend(self.rules.iter().enumerate().map(|(i, rule)| {
+            let selected = self
+                .projects
+                .iter()
+                .find(|p| Some(p.id) == rule.project_id)
+                .cloned();
+            column![
+                row![
+                    text_input("Description contains...", &rule.pattern)
+                        .on_input(move |v| RulesMessage::PatternEdited(i, v)),
+                    button("Remove")
+                        .on_press(RulesMessage::Remove(i))
+                        .style(button::danger),
+                ]
+                .spacing(10),
+                row![
+                    pick_list(
+                        project_options.clone(),
+                        Some(MaybeProject::from(selected)),
+                        move |p| RulesMessage::ProjectSelected(i, p)
+                    )
+                    .width(Length::FillPortion(1)),
+                    text_input("tag, another tag", &rule.tags)
+                        .on_input(move |v| RulesMessage::TagsEdited(i, v))
+                        .width(Length::FillPortion(1)),
+                    checkbox("Overwrite", rule.overwrite).on_toggle(move |v| {
+                        RulesMessage::OverwriteToggled(i, v)
+                    }),
+                ]
+                .spacing(10)
+                .align_y(Center),
+                horizontal_rule(0.5),
+            ]
+            .spacing(5)
+            .into()
+        }))
+        .push(
+            row![
+                button("Add rule")
+                    .on_press(RulesMessage::Add)
+                    .style(button::secondary)
+                    .width(Length::FillPortion(1)),
+                button("Save")
+                    .on_press(RulesMessage::Save)
+                    .style(button::primary)
+                    .width(Length::FillPortion(1)),
+            ]
+            .spacing(20),
+        )
+        .spacing(10);
+
+        scrollable(container(content).center_x(Fill).padding(40)).into()
+    }
+
+    pub fn update(&mut self, message: RulesMessage) {
+        match message {
+            RulesMessage::PatternEdited(i, pattern) => {
+                if let Some(rule) = self.rules.get_mut(i) {
+                    rule.pattern = pattern;
+                }
+            }
+            RulesMessage::ProjectSelected(i, project) => {
+                if let Some(rule) = self.rules.get_mut(i) {
+                    rule.project_id =
+                        Option::<Project>::from(project).map(|p| p.id);
+                }
+            }
+            RulesMessage::TagsEdited(i, tags) => {
+                if let Some(rule) = self.rules.get_mut(i) {
+                    rule.tags = tags;
+                }
+            }
+            RulesMessage::OverwriteToggled(i, overwrite) => {
+                if let Some(rule) = self.rules.get_mut(i) {
+                    rule.overwrite = overwrite;
+                }
+            }
+            RulesMessage::Add => self.rules.push(Rule::default()),
+            RulesMessage::Remove(i) => {
+                if i < self.rules.len() {
+                    self.rules.remove(i);
+                }
+            }
+            RulesMessage::Save | RulesMessage::Abort => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{apply_rules, parse_quick_add, QuickAdd, Rule};
+    use crate::project::{Project, ProjectId};
+
+    fn rule(pattern: &str, project: Option<u64>, tags: &str) -> Rule {
+        Rule {
+            pattern: pattern.to_string(),
+            project_id: project.map(ProjectId::from),
+            tags: tags.to_string(),
+            overwrite: false,
+        }
+    }
+
+    #[test]
+    fn test_apply_rules_precedence() {
+        let rules = [
+            rule("standup", None, "recurring"),
+            rule("STANDUP", Some(1), "meeting"),
+            rule("up", Some(2), "meeting, misc"),
+            rule("", Some(3), "never"),
+        ];
+        let (project, tags) = apply_rules(&rules, "Daily standup", None, &[]);
+        assert_eq!(project, Some(ProjectId::from(1)));
+        assert_eq!(tags, ["recurring", "meeting", "misc"]);
+
+        let (project, tags) =
+            apply_rules(&rules, "Nothing here", None, &["own".to_string()]);
+        assert_eq!(project, None);
+        assert_eq!(tags, ["own"]);
+    }
+
+    #[test]
+    fn test_apply_rules_keeps_project_unless_overwriting() {
+        let mut rules = [rule("standup", Some(1), "")];
+        let chosen = Some(ProjectId::from(5));
+        assert_eq!(apply_rules(&rules, "standup", chosen, &[]).0, chosen);
+        rules[0].overwrite = true;
+        assert_eq!(
+            apply_rules(&rules, "standup", chosen, &[]).0,
+            Some(ProjectId::from(1))
+        );
+    }
+
+    #[test]
+    fn test_parse_quick_add() {
+        let projects: Vec<Project> =
+            serde_json::from_value(serde_json::json!([
+                {"id": 1, "name": "Client X", "active": true, "color": "#000"},
+                {"id": 2, "name": "Other", "active": true, "color": "#000",
+                 "workspace_id": 9},
+            ]))
+            .unwrap();
+        let known = vec!["Billable".to_string()];
+        let parsed = parse_quick_add(
+            "Design  work @clientx #billable #new # @",
+            &projects,
+            1.into(),
+            &known,
+        )
+        .unwrap();
+        assert_eq!(parsed.description, "Design  work # @");
+        assert_eq!(parsed.project_id, Some(1.into()));
+        assert_eq!(parsed.tags, vec!["Billable", "new"]);
+
+        // Plain descriptions are left as typed.
+        let text = " Reply to @alice  about #123 ";
+        let parsed = parse_quick_add(text, &projects, 1.into(), &known);
+        assert_eq!(
+            parsed,
+            Ok(QuickAdd {
+                description: text.to_string(),
+                ..QuickAdd::default()
+            })
+        );
+        // @other belongs to another workspace.
+        let parsed =
+            parse_quick_add("Work @other #x", &projects, 1.into(), &known);
+        assert_eq!(parsed.unwrap().description, "Work @other");
+        assert!(
+            parse_quick_add("@clientx @clientx", &projects, 1.into(), &[])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,338 @@
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use iced::widget::{
+    button, column, container, horizontal_rule, row, scrollable, text,
+    text_input,
+};
+use iced::{Element, Fill, Length, Right, Task as Command};
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{Client, Result as NetResult};
+use crate::customization::Customization;
+use crate::project::{Project, ProjectId};
+use crate::time_entry::TimeEntry;
+use crate::workspace::WorkspaceId;
+
+/// A single match returned by the Reports search API.
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub id: u64,
+    pub description: Option<String>,
+    pub project_id: Option<ProjectId>,
+    pub start: DateTime<Local>,
+}
+
+impl SearchResult {
+    pub async fn search(
+        workspace_id: WorkspaceId,
+        description: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        client: &Client,
+    ) -> NetResult<Vec<Self>> {
+        #[derive(Serialize)]
+        struct Query<'a> {
+            description: &'a str,
+            start_date: NaiveDate,
+            end_date: NaiveDate,
+            page_size: u32,
+        }
+
+        #[derive(Deserialize)]
+        struct Row {
+            description: Option<String>,
+            project_id: Option<ProjectId>,
+            time_entries: Vec<RowEntry>,
+        }
+
+        #[derive(Deserialize)]
+        struct RowEntry {
+            id: u64,
+            start: DateTime<Local>,
+        }
+
+        debug!("Searching time entries for '{description}'...");
+        let mut res = client
+            .post(
+                [
+                    Client::BASE_URL.to_string(),
+                    format!(
+                        "/reports/api/v3/workspace/{}/search/time_entries",
+                        workspace_id
+                    ),
+                ]
+                .join(""),
+            )
+            .body_json(&Query {
+                description,
+                start_date,
+                end_date,
+                page_size: 50,
+            })?
+            .send()
+            .await?;
+        Client::check_status(&mut res).await?;
+        let rows = res.body_json::<Vec<Row>>().await?;
+        let mut results: Vec<_> = rows
+            .into_iter()
+            .flat_map(|row| {
+                row.time_entries.into_iter().map(move |e| Self {
+                    id: e.id,
+                    description: row.description.clone(),
+                    project_id: row.project_id,
+                    start: e.start,
+                })
+            })
+            .collect();
+        results.sort_by_key(|r| std::cmp::Reverse(r.start));
+        Ok(results)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchScreen {
+    api_token: String,
+    workspace_id: WorkspaceId,
+    query: String,
+    start_text: String,
+    end_text: String,
+    server_results: Option<Vec<SearchResult>>,
+    loading: bool,
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum SearchMessage {
+    QueryEdited(String),
+    StartEdited(String),
+    EndEdited(String),
+    SearchServer,
+    ServerResults(Result<Vec<SearchResult>, String>),
+    SelectRemote(u64),
+    Selected(Box<TimeEntry>),
+    Abort,
+    Error(String),
+}
+
+impl SearchScreen {
+    pub fn new(
+        api_token: &str,
+        workspace_id: WorkspaceId,
+        customization: &Customization,
+    ) -> Self {
+        let today = Local::now().date_naive();
+        Self {
+            api_token: api_token.to_string(),
+            workspace_id,
+            query: String::new(),
+            start_text: customization
+                .format_date(&(today - Duration::days(365))),
+            end_text: customization.format_date(&today),
+            server_results: None,
+            loading: false,
+            error: None,
+        }
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        entries: &'a [TimeEntry],
+        projects: &'a [Project],
+        customization: &'a Customization,
+    ) -> Element<'a, SearchMessage> {
+        let needle = self.query.to_lowercase();
+        let local_results: Vec<_> = if needle.is_empty() {
+            vec![]
+        } else {
+            entries
+                .iter()
+                .filter(|e| {
+                    e.description
+                        .as_ref()
+                        .is_some_and(|d| d.to_lowercase().contains(&needle))
+                })
+                .map(|e| {
+                    result_row(
+                        &e.description,
+                        e.project_id,
+                        &e.start,
+                        projects,
+                        customization,
+                    )
+                    .on_press_with(|| {
+                        SearchMessage::Selected(Box::new(e.clone()))
+                    })
+                    .into()
+                })
+                .collect()
+        };
+        let local_count = local_results.len();
+
+        let server_section: Element<'a, SearchMessage> =
+            match &self.server_results {
+                None if self.loading => text("Searching...").into(),
+                None => text("Not searched yet.").into(),
+                Some(results) if results.is_empty() => {
+                    text("No matches on the server.").into()
+                }
+                Some(results) => column(results.iter().map(|r| {
+                    result_row(
+                        &r.description,
+                        r.project_id,
+                        &r.start,
+                        projects,
+                        customization,
+                    )
+                    .on_press(SearchMessage::SelectRemote(r.id))
+                    .into()
+                }))
+                .into(),
+            };
+
+        let content = column![
+            column![button("X")
+                .on_press(SearchMessage::Abort)
+                .style(button::text),]
+            .align_x(Right)
+            .width(Fill),
+            text_input("Description", &self.query)
+                .id("search-input")
+                .on_input(SearchMessage::QueryEdited)
+                .on_submit(SearchMessage::SearchServer),
+            row![
+                text_input("From", &self.start_text)
+                    .id("search-start-input")
+                    .on_input(SearchMessage::StartEdited),
+                text_input("To", &self.end_text)
+                    .id("search-end-input")
+                    .on_input(SearchMessage::EndEdited),
+                button("Search server")
+                    .on_press_maybe(
+                        (!self.query.is_empty() && !self.loading)
+                            .then_some(SearchMessage::SearchServer)
+                    )
+                    .style(button::primary),
+            ]
+            .spacing(10),
+        ]
+        .push_maybe(self.error.clone().map(|e| text(e).style(text::danger)))
+        .push(text(format!("Loaded entries ({local_count})")).size(18))
+        .push(horizontal_rule(0.5))
+        .push(column(local_results))
+        .push(text("Server results").size(18))
+        .push(horizontal_rule(0.5))
+        .push(server_section)
+        .spacing(10);
+
+        scrollable(container(content).center_x(Fill).padding(40)).into()
+    }
+
+    pub fn update(
+        &mut self,
+        message: SearchMessage,
+        customization: &Customization,
+    ) -> Command<SearchMessage> {
+        match message {
+            SearchMessage::QueryEdited(query) => {
+                self.query = query;
+                self.server_results = None;
+            }
+            SearchMessage::StartEdited(start) => self.start_text = start,
+            SearchMessage::EndEdited(end) => self.end_text = end,
+            SearchMessage::SearchServer => {
+                if self.query.is_empty() {
+                    return Command::none();
+                }
+                let Ok(start_date) = customization.parse_date(&self.start_text)
+                else {
+                    return Command::done(SearchMessage::Error(format!(
+                        "Invalid start date: {}",
+                        self.start_text
+                    )));
+                };
+                let Ok(end_date) = customization.parse_date(&self.end_text)
+                else {
+                    return Command::done(SearchMessage::Error(format!(
+                        "Invalid end date: {}",
+                        self.end_text
+                    )));
+                };
+                self.error = None;
+                self.loading = true;
+                self.server_results = None;
+                let api_token = self.api_token.clone();
+                let workspace_id = self.workspace_id;
+                let query = self.query.clone();
+                return Command::future(async move {
+                    let client = Client::from_api_token(&api_token);
+                    SearchMessage::ServerResults(
+                        SearchResult::search(
+                            workspace_id,
+                            &query,
+                            start_date,
+                            end_date,
+                            &client,
+                        )
+                        .await
+                        .map_err(|e| e.to_string()),
+                    )
+                });
+            }
+            SearchMessage::ServerResults(results) => {
+                self.loading = false;
+                match results {
+                    Ok(results) => self.server_results = Some(results),
+                    Err(e) => self.error = Some(e),
+                }
+            }
+            SearchMessage::SelectRemote(id) => {
+                let api_token = self.api_token.clone();
+                return Command::future(async move {
+                    let client = Client::from_api_token(&api_token);
+                    match TimeEntry::get(id, &client).await {
+                        Ok(entry) => SearchMessage::Selected(Box::new(entry)),
+                        Err(e) => SearchMessage::Error(e.to_string()),
+                    }
+                });
+            }
+            SearchMessage::Selected(_) | SearchMessage::Abort => {}
+            SearchMessage::Error(err) => {
+                self.loading = false;
+                self.error = Some(err);
+            }
+        };
+        Command::none()
+    }
+}
+
+fn result_row<'a>(
+    description: &Option<String>,
+    project_id: Option<ProjectId>,
+    start: &DateTime<Local>,
+    projects: &[Project],
+    customization: &Customization,
+) -> button::Button<'a, SearchMessage> {
+    let project = projects
+        .iter()
+        .find(|p| Some(p.id) == project_id)
+        .map_or("No project".to_string(), |p| p.name.clone());
+    button(
+        row![
+            column![
+                text(
+                    description
+                        .clone()
+                        .unwrap_or("<NO DESCRIPTION>".to_string())
+                )
+                .wrapping(text::Wrapping::None),
+                text(project).size(12),
+            ]
+            .width(Length::Fill),
+            text(customization.format_date(&start.date_naive())),
+        ]
+        .spacing(10),
+    )
+    .clip(true)
+    .style(button::text)
+    .width(Fill)
+}
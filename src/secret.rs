@@ -0,0 +1,92 @@
+//! Secrets kept in the OS keyring instead of the state file.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use keyring::{Entry, Error};
+use lazy_static::lazy_static;
+
+const SERVICE: &str = "toggl-tracker";
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Secret {
+    ApiToken,
+    ProxyPassword,
+}
+
+impl Secret {
+    fn user(self) -> &'static str {
+        match self {
+            Self::ApiToken => "api_token",
+            Self::ProxyPassword => "proxy_password",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::ApiToken => "API token",
+            Self::ProxyPassword => "proxy password",
+        }
+    }
+}
+
+lazy_static! {
+    /// Secrets last read from or written to the keyring, to skip the
+    /// blocking keyring calls when saving an unchanged secret.
+    static ref KNOWN: Mutex<HashMap<Secret, String>> =
+        Mutex::new(HashMap::new());
+}
+
+fn remember(secret: Secret, value: Option<&str>) {
+    let mut known = KNOWN.lock().expect("Secret cache poisoned");
+    match value {
+        Some(value) => known.insert(secret, value.to_string()),
+        None => known.remove(&secret),
+    };
+}
+
+/// Whether the secret was read from or written to the keyring this run.
+pub fn is_known(secret: Secret) -> bool {
+    KNOWN
+        .lock()
+        .expect("Secret cache poisoned")
+        .contains_key(&secret)
+}
+
+fn entry(secret: Secret) -> Result<Entry, String> {
+    Entry::new(SERVICE, secret.user())
+        .map_err(|e| format!("Keyring unavailable: {e}"))
+}
+
+pub fn load(secret: Secret) -> Result<String, String> {
+    let value = entry(secret)?.get_password().map_err(|e| {
+        format!("Can't read the {} from the keyring: {e}", secret.name())
+    })?;
+    remember(secret, Some(&value));
+    Ok(value)
+}
+
+pub fn store(secret: Secret, value: &str) -> Result<(), String> {
+    // Saves are frequent, don't talk to the keyring for an unchanged secret.
+    if KNOWN
+        .lock()
+        .expect("Secret cache poisoned")
+        .get(&secret)
+        .is_some_and(|known| known == value)
+    {
+        return Ok(());
+    }
+    entry(secret)?.set_password(value).map_err(|e| {
+        format!("Can't store the {} in the keyring: {e}", secret.name())
+    })?;
+    remember(secret, Some(value));
+    Ok(())
+}
+
+pub fn delete(secret: Secret) -> Result<(), String> {
+    remember(secret, None);
+    match entry(secret)?.delete_credential() {
+        Ok(()) | Err(Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Can't remove the {}: {e}", secret.name())),
+    }
+}
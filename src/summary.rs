@@ -0,0 +1,446 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Local};
+use iced::widget::{
+    button, column, container, horizontal_rule, row, scrollable, text,
+};
+use iced::{Element, Fill, Right, Task as Command};
+use log::error;
+
+use crate::client::Client;
+use crate::customization::Customization;
+use crate::project::ProjectId;
+use crate::time_entry::{to_start_of_week, total_between, TimeEntry};
+use crate::workspace::WorkspaceId;
+
+/// Totals of a week and the one before it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WeekComparison {
+    pub this_week: Duration,
+    pub last_week: Duration,
+    /// Last week's total up to the same point of the week as now.
+    pub last_week_so_far: Duration,
+    pub last_week_start: DateTime<Local>,
+    /// Entries started this week without a project.
+    pub unassigned_this_week: usize,
+}
+
+/// Start of the week `weeks_back` weeks before the one containing `now`.
+pub fn week_start(now: DateTime<Local>, weeks_back: u32) -> DateTime<Local> {
+    // Not `start - 7 days`: a DST switch may lie in between.
+    (0..weeks_back).fold(to_start_of_week(now), |start, _| {
+        to_start_of_week(start - Duration::days(1))
+    })
+}
+
+impl WeekComparison {
+    pub fn new(
+        entries: &[TimeEntry],
+        running_entry: Option<&TimeEntry>,
+        now: DateTime<Local>,
+    ) -> Self {
+        Self::for_week(entries, running_entry, to_start_of_week(now), now)
+    }
+
+    /// Compare the week starting at `this_week_start` with the one before;
+    /// a week that is not over yet counts only up to `now`.
+    pub fn for_week(
+        entries: &[TimeEntry],
+        running_entry: Option<&TimeEntry>,
+        this_week_start: DateTime<Local>,
+        now: DateTime<Local>,
+    ) -> Self {
+        let last_week_start =
+            to_start_of_week(this_week_start - Duration::days(1));
+        let next_week_start =
+            to_start_of_week(this_week_start + Duration::days(8));
+        let end = now.min(next_week_start);
+        let same_point = last_week_start + (end - this_week_start);
+        let unassigned_this_week = entries
+            .iter()
+            .chain(running_entry)
+            .filter(|e| {
+                e.project_id.is_none()
+                    && e.start >= this_week_start
+                    && e.start < end
+            })
+            .count();
+        Self {
+            this_week: total_between(
+                entries,
+                running_entry,
+                this_week_start,
+                end,
+            ),
+            last_week: total_between(
+                entries,
+                running_entry,
+                last_week_start,
+                this_week_start,
+            ),
+            last_week_so_far: total_between(
+                entries,
+                running_entry,
+                last_week_start,
+                same_point,
+            ),
+            last_week_start,
+            unassigned_this_week,
+        }
+    }
+}
+
+/// Signed difference with a percentage, e.g. "+1:00:00 (+25%)".
+fn format_delta(
+    current: Duration,
+    previous: Duration,
+    customization: &Customization,
+) -> String {
+    let delta = current - previous;
+    let sign = if delta < Duration::zero() { "-" } else { "+" };
+    let amount = customization.format_duration(&delta.abs());
+    if previous.is_zero() {
+        format!("{sign}{amount}")
+    } else {
+        let percent = delta.num_seconds() * 100 / previous.num_seconds();
+        format!("{sign}{amount} ({percent:+}%)")
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SummaryScreen {
+    api_token: String,
+    workspace_id: Option<WorkspaceId>,
+    /// Project filter of the entry list when the screen was opened.
+    hidden_projects: HashSet<Option<ProjectId>>,
+    /// Text filter of the entry list when the screen was opened.
+    filter: String,
+    /// How many weeks before the current one are shown, 0 for this week.
+    weeks_back: u32,
+    /// Entries fetched for a past week and the one before it.
+    history: Option<Vec<TimeEntry>>,
+    loading: bool,
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum SummaryMessage {
+    PreviousWeek,
+    NextWeek,
+    HistoryLoaded(u32, Result<Vec<TimeEntry>, String>),
+    Abort,
+}
+
+impl SummaryScreen {
+    pub fn new(
+        api_token: &str,
+        workspace_id: Option<WorkspaceId>,
+        hidden_projects: HashSet<Option<ProjectId>>,
+        filter: String,
+    ) -> Self {
+        Self {
+            api_token: api_token.to_string(),
+            workspace_id,
+            hidden_projects,
+            filter,
+            ..Self::default()
+        }
+    }
+
+    /// Whether `entry` is listed with the filters, the same as in the list.
+    fn counts(&self, entry: &TimeEntry) -> bool {
+        !self.hidden_projects.contains(&entry.project_id)
+            && entry.matches_filter(&self.filter)
+    }
+
+    fn is_filtered(&self) -> bool {
+        !self.hidden_projects.is_empty() || !self.filter.trim().is_empty()
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        entries: &'a [TimeEntry],
+        running_entry: Option<&'a TimeEntry>,
+        has_more_entries: bool,
+        customization: &'a Customization,
+    ) -> Element<'a, SummaryMessage> {
+        let now = Local::now();
+        let fmt = |d: &Duration| customization.format_duration(d);
+        let running_entry = running_entry.filter(|e| self.counts(e));
+        let navigation = row![
+            button(text("\u{2190}").center())
+                .on_press_maybe(
+                    (!self.loading).then_some(SummaryMessage::PreviousWeek)
+                )
+                .style(button::secondary),
+            button(text("\u{2192}").center())
+                .on_press_maybe(
+                    (!self.loading && self.weeks_back > 0)
+                        .then_some(SummaryMessage::NextWeek)
+                )
+                .style(button::secondary),
+        ]
+        .spacing(5);
+        let header = column![column![button("X")
+            .on_press(SummaryMessage::Abort)
+            .style(button::text),]
+        .align_x(Right)
+        .width(Fill),]
+        .push_maybe(self.is_filtered().then(|| {
+            text("Only entries shown with the list filters are counted.")
+                .size(14)
+        }));
+
+        let content = if self.weeks_back == 0 {
+            let counted: Vec<_> =
+                entries.iter().filter(|e| self.counts(e)).cloned().collect();
+            let comparison = WeekComparison::new(&counted, running_entry, now);
+            let oldest_loaded = entries.last().map(|e| e.start);
+            let incomplete = has_more_entries
+                && oldest_loaded.is_none_or(|s| s > comparison.last_week_start);
+            header
+                .push(
+                    row![text("This week vs last week").size(18).width(Fill)]
+                        .push(navigation),
+                )
+                .push(horizontal_rule(0.5))
+                .push(summary_row(
+                    "This week (in progress)",
+                    fmt(&comparison.this_week),
+                ))
+                .push(summary_row(
+                    "Last week, same point",
+                    fmt(&comparison.last_week_so_far),
+                ))
+                .push(summary_row(
+                    "Difference",
+                    format_delta(
+                        comparison.this_week,
+                        comparison.last_week_so_far,
+                        customization,
+                    ),
+                ))
+                .push(summary_row(
+                    "Last week, whole",
+                    fmt(&comparison.last_week),
+                ))
+                .push_maybe((comparison.unassigned_this_week > 0).then(|| {
+                    text(format!(
+                        "{} entries this week have no project: use Filter > \
+                         Only without project to assign them.",
+                        comparison.unassigned_this_week
+                    ))
+                    .size(14)
+                }))
+                .push_maybe(incomplete.then(|| {
+                    text(
+                        "Last week is not fully loaded yet: use \"Load more\" \
+                         in the entry list for exact totals.",
+                    )
+                    .style(text::danger)
+                }))
+        } else {
+            let start = week_start(now, self.weeks_back);
+            let title = format!(
+                "Week of {} vs the week before",
+                customization.format_date(&start.date_naive())
+            );
+            let header = header
+                .push(row![text(title).size(18).width(Fill)].push(navigation))
+                .push(horizontal_rule(0.5));
+            match (&self.history, &self.error) {
+                (_, Some(error)) => {
+                    header.push(text(error).style(text::danger))
+                }
+                (None, None) => header.push(text("Loading...")),
+                (Some(history), None) => {
+                    let counted: Vec<_> = history
+                        .iter()
+                        .filter(|e| self.counts(e))
+                        .cloned()
+                        .collect();
+                    let comparison = WeekComparison::for_week(
+                        &counted,
+                        running_entry,
+                        start,
+                        now,
+                    );
+                    header
+                        .push(summary_row(
+                            "Selected week",
+                            fmt(&comparison.this_week),
+                        ))
+                        .push(summary_row(
+                            "The week before",
+                            fmt(&comparison.last_week),
+                        ))
+                        .push(summary_row(
+                            "Difference",
+                            format_delta(
+                                comparison.this_week,
+                                comparison.last_week,
+                                customization,
+                            ),
+                        ))
+                }
+            }
+        }
+        .spacing(10);
+
+        scrollable(container(content).center_x(Fill).padding(40)).into()
+    }
+
+    pub fn update(
+        &mut self,
+        message: SummaryMessage,
+    ) -> Command<SummaryMessage> {
+        match message {
+            SummaryMessage::PreviousWeek => self.weeks_back += 1,
+            SummaryMessage::NextWeek => {
+                self.weeks_back = self.weeks_back.saturating_sub(1)
+            }
+            SummaryMessage::HistoryLoaded(weeks_back, result) => {
+                // Ignore answers for a week that is no longer shown.
+                if weeks_back == self.weeks_back {
+                    self.loading = false;
+                    match result {
+                        Ok(entries) => self.history = Some(entries),
+                        Err(e) => self.error = Some(e),
+                    }
+                }
+                return Command::none();
+            }
+            SummaryMessage::Abort => return Command::none(),
+        }
+        self.history = None;
+        self.error = None;
+        if self.weeks_back == 0 {
+            self.loading = false;
+            return Command::none();
+        }
+        self.loading = true;
+        let weeks_back = self.weeks_back;
+        let now = Local::now();
+        let start = week_start(now, weeks_back + 1);
+        let end = week_start(now, weeks_back - 1);
+        let token = self.api_token.clone();
+        let workspace_id = self.workspace_id;
+        Command::future(async move {
+            let client = Client::from_api_token(&token);
+            let result = TimeEntry::load_range(start, end, &client)
+                .await
+                .map(|entries| {
+                    entries
+                        .into_iter()
+                        // The running entry is passed separately.
+                        .filter(|e| {
+                            !e.is_running()
+                                && workspace_id
+                                    .is_none_or(|ws| e.workspace_id == ws)
+                        })
+                        .collect()
+                })
+                .map_err(|e| {
+                    error!("Failed to load past weeks: {e}");
+                    e.to_string()
+                });
+            SummaryMessage::HistoryLoaded(weeks_back, result)
+        })
+    }
+}
+
+fn summary_row<'a>(
+    label: &'a str,
+    value: String,
+) -> Element<'a, SummaryMessage> {
+    row![text(label).width(Fill), text(value)]
+        .spacing(10)
+        .into()
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Local};
+
+    use super::{format_delta, week_start, WeekComparison};
+    use crate::customization::Customization;
+    use crate::time_entry::{test_entry, to_start_of_week};
+
+    #[test]
+    fn test_week_comparison() {
+        let now = Local::now();
+        let this_week_start = to_start_of_week(now);
+        let last_week_start =
+            to_start_of_week(this_week_start - Duration::days(1));
+        let entries = [
+            test_entry(now - Duration::seconds(30), Some(now), None),
+            // Tracked on last Monday right after midnight.
+            test_entry(
+                last_week_start,
+                Some(last_week_start + Duration::seconds(20)),
+                None,
+            ),
+            // Crosses into this week: split between both.
+            test_entry(
+                this_week_start - Duration::seconds(5),
+                Some(this_week_start + Duration::seconds(5)),
+                None,
+            ),
+        ];
+        let comparison = WeekComparison::new(&entries, None, now);
+        assert_eq!(comparison.this_week, Duration::seconds(35));
+        assert_eq!(comparison.last_week, Duration::seconds(25));
+        assert!(comparison.last_week_so_far >= Duration::seconds(20));
+        // The entry crossing midnight started last week.
+        assert_eq!(comparison.unassigned_this_week, 1);
+    }
+
+    #[test]
+    fn test_past_week_comparison() {
+        let now = Local::now();
+        let selected = week_start(now, 2);
+        assert_eq!(week_start(now, 0), to_start_of_week(now));
+        assert!(selected < week_start(now, 1));
+        let before = week_start(now, 3);
+        let entries = [
+            test_entry(selected, Some(selected + Duration::hours(3)), None),
+            test_entry(before, Some(before + Duration::hours(1)), None),
+            // In the following week: not counted.
+            test_entry(
+                week_start(now, 1),
+                Some(week_start(now, 1) + Duration::hours(5)),
+                None,
+            ),
+        ];
+        let comparison =
+            WeekComparison::for_week(&entries, None, selected, now);
+        assert_eq!(comparison.this_week, Duration::hours(3));
+        assert_eq!(comparison.last_week, Duration::hours(1));
+        assert_eq!(comparison.last_week_so_far, Duration::hours(1));
+    }
+
+    #[test]
+    fn test_format_delta() {
+        let customization = Customization::default();
+        assert_eq!(
+            format_delta(
+                Duration::hours(5),
+                Duration::hours(4),
+                &customization
+            ),
+            "+1:00:00 (+25%)"
+        );
+        assert_eq!(
+            format_delta(
+                Duration::hours(2),
+                Duration::hours(4),
+                &customization
+            ),
+            "-2:00:00 (-50%)"
+        );
+        assert_eq!(
+            format_delta(Duration::hours(1), Duration::zero(), &customization),
+            "+1:00:00"
+        );
+    }
+}
@@ -1,13 +1,23 @@
-use chrono::{DateTime, Duration, Local};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, Timelike};
+#[cfg(feature = "gui")]
 use iced::alignment::Vertical;
-use iced::widget::{button, column, container, row, text};
+#[cfg(feature = "gui")]
+use iced::widget::{
+    button, column, container, mouse_area, pick_list, row, text, text_input,
+};
+#[cfg(feature = "gui")]
 use iced::{Color, Element, Length};
+#[cfg(feature = "gui")]
 use iced_aw::badge;
+use itertools::Itertools;
 use log::debug;
 use serde::{Deserialize, Serialize, Serializer};
 
 use crate::client::{Client, Result as NetResult};
-use crate::project::{Project, ProjectId};
+use crate::customization::{Customization, DurationFormat};
+use crate::project::ProjectId;
+#[cfg(feature = "gui")]
+use crate::project::{MaybeProject, Project};
 use crate::workspace::WorkspaceId;
 
 fn datetime_serialize_utc<S: Serializer>(
@@ -46,7 +56,20 @@ pub struct TimeEntry {
     pub workspace_id: WorkspaceId,
 }
 
+/// `duration` the API uses for an entry that is still running; any negative
+/// value means running.
+pub const RUNNING_DURATION: i64 = -1;
+
 impl TimeEntry {
+    pub fn is_running(&self) -> bool {
+        self.duration < 0
+    }
+
+    /// Start of the entry if it is still running.
+    pub fn running_since(&self) -> Option<DateTime<Local>> {
+        self.is_running().then_some(self.start)
+    }
+
     pub async fn load(
         before: Option<DateTime<Local>>,
         client: &Client,
@@ -68,15 +91,67 @@ impl TimeEntry {
             Ok(entries)
         } else {
             // The API parses this bound as inclusive, we don't need duplicates
-            Ok(entries[1..].to_vec())
+            Ok(entries.into_iter().skip(1).collect())
+        }
+    }
+
+    /// Entries that started in `[start, end)`.
+    pub async fn load_range(
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        client: &Client,
+    ) -> NetResult<Vec<Self>> {
+        #[derive(Serialize)]
+        struct QueryParams {
+            #[serde(serialize_with = "datetime_serialize_utc")]
+            start_date: DateTime<Local>,
+            #[serde(serialize_with = "datetime_serialize_utc")]
+            end_date: DateTime<Local>,
         }
+
+        debug!("Fetching time entries from {start} to {end}...");
+        let mut res = client
+            .get([Client::BASE_URL, "/api/v9/me/time_entries"].join(""))
+            .query(&QueryParams {
+                start_date: start,
+                end_date: end,
+            })?
+            .send()
+            .await?;
+        Client::check_status(&mut res).await?;
+        res.body_json().await
+    }
+
+    pub async fn get(id: u64, client: &Client) -> NetResult<Self> {
+        debug!("Fetching a time entry {id}...");
+        let mut res = client
+            .get(
+                [Client::BASE_URL, &format!("/api/v9/me/time_entries/{id}")]
+                    .join(""),
+            )
+            .send()
+            .await?;
+        Client::check_status(&mut res).await?;
+        res.body_json().await
+    }
+
+    /// Currently running entry, if any.
+    pub async fn current(client: &Client) -> NetResult<Option<Self>> {
+        debug!("Fetching the running time entry...");
+        let mut res = client
+            .get([Client::BASE_URL, "/api/v9/me/time_entries/current"].join(""))
+            .send()
+            .await?;
+        Client::check_status(&mut res).await?;
+        // Toggl answers with a literal `null` when nothing is running.
+        res.body_json().await
     }
 
     pub fn split_running(all_entries: Vec<Self>) -> (Option<Self>, Vec<Self>) {
         match &all_entries[..] {
             [] => (None, vec![]),
             [head, rest @ ..] => {
-                if head.duration < 0 {
+                if head.is_running() {
                     (Some(head.clone()), rest.to_vec())
                 } else {
                     (None, all_entries)
@@ -85,7 +160,32 @@ impl TimeEntry {
         }
     }
 
-    pub async fn save(&self, client: &Client) -> NetResult<()> {
+    pub async fn load_one(id: u64, client: &Client) -> NetResult<Self> {
+        debug!("Fetching a time entry {id}...");
+        let mut res = client
+            .get(
+                [Client::BASE_URL, &format!("/api/v9/me/time_entries/{id}")]
+                    .join(""),
+            )
+            .send()
+            .await?;
+        Client::check_status(&mut res).await?;
+        res.body_json().await
+    }
+
+    /// Whether both versions agree on everything the user can edit.
+    pub fn same_content(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.description == other.description
+            && self.project_id == other.project_id
+            && self.start == other.start
+            && self.stop == other.stop
+            && self.duration == other.duration
+            && self.billable == other.billable
+            && self.tags.iter().sorted().eq(other.tags.iter().sorted())
+    }
+
+    pub async fn save(&self, client: &Client) -> NetResult<Self> {
         debug!("Updating a time entry {}...", self.id);
         let mut res = client
             .put(
@@ -101,12 +201,44 @@ impl TimeEntry {
             .body_json(&self)?
             .send()
             .await?;
-        Client::check_status(&mut res).await
+        Client::check_status(&mut res).await?;
+        res.body_json().await
+    }
+
+    /// Copy of the entry stopped at `stop`.
+    pub fn stopped_at(&self, stop: DateTime<Local>) -> Self {
+        Self {
+            stop: Some(stop),
+            duration: (stop - self.start).num_seconds(),
+            ..self.clone()
+        }
+    }
+
+    /// Stop the running entry now, rounded if `customization` asks for it,
+    /// and return it as stopped.
+    pub async fn stop_rounded(
+        &self,
+        client: &Client,
+        customization: &Customization,
+    ) -> NetResult<Self> {
+        let stopped = self
+            .stopped_at(customization.stop_to_save(self.start, Local::now()));
+        if customization.round_on_save {
+            // `/stop` always stops now, save the rounded stop instead.
+            stopped.save(client).await?;
+        } else {
+            self.stop(client).await?;
+        }
+        Ok(stopped)
     }
 
+    /// Stop the entry; fails with [`is_already_stopped`] if it was stopped
+    /// elsewhere in the meantime.
     pub async fn stop(&self, client: &Client) -> NetResult<()> {
         debug!("Stopping a time entry {}...", self.id);
-        assert!(self.stop.is_none());
+        if !self.is_running() {
+            return Err(already_stopped());
+        }
         let mut res = client
             .patch(
                 [
@@ -120,7 +252,12 @@ impl TimeEntry {
             )
             .send()
             .await?;
-        Client::check_status(&mut res).await
+        match Client::check_status(&mut res).await {
+            Err(e) if e.status() == surf::StatusCode::Conflict => {
+                Err(already_stopped())
+            }
+            other => other,
+        }
     }
 
     pub async fn delete(self, client: &Client) -> NetResult<()> {
@@ -141,23 +278,123 @@ impl TimeEntry {
         Client::check_status(&mut res).await
     }
 
-    fn duration_string(&self) -> String {
-        let diff = self
-            .stop
+    /// Start a new running entry with the same metadata as this one.
+    pub async fn continue_entry(&self, client: &Client) -> NetResult<Self> {
+        debug!("Continuing a time entry {}...", self.id);
+        let entry = CreateTimeEntry {
+            billable: self.billable,
+            task_id: self.task_id,
+            ..CreateTimeEntry::new(
+                self.description.clone(),
+                self.workspace_id,
+                self.project_id,
+                self.tags.clone(),
+            )
+        };
+        entry.create(client).await
+    }
+
+    /// Create a stopped copy with the same times.
+    pub async fn copy_entry(&self, client: &Client) -> NetResult<Self> {
+        debug!("Copying a time entry {}...", self.id);
+        let entry = CreateTimeEntry {
+            billable: self.billable,
+            task_id: self.task_id,
+            ..CreateTimeEntry::new(
+                self.description.clone(),
+                self.workspace_id,
+                self.project_id,
+                self.tags.clone(),
+            )
+            .with_times(self.start, self.duration)
+        };
+        entry.create(client).await
+    }
+
+    /// One-line summary for the clipboard.
+    pub fn details(
+        &self,
+        project_name: Option<&str>,
+        customization: &Customization,
+    ) -> String {
+        let mut details = format!(
+            "{} {}",
+            customization.format_datetime(&Some(self.start)),
+            self.description.as_deref().unwrap_or("<NO DESCRIPTION>")
+        );
+        if let Some(project) = project_name {
+            details.push_str(&format!(" [{project}]"));
+        }
+        if !self.tags.is_empty() {
+            details.push_str(&format!(" #{}", self.tags.join(" #")));
+        }
+        details
+            .push_str(&format!(" ({})", self.duration_string(customization)));
+        details
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.stop
             .unwrap_or(Local::now().with_timezone(&self.start.timezone()))
-            - self.start;
-        duration_to_hms(&diff)
+            - self.start
+    }
+
+    pub fn duration_string(&self, customization: &Customization) -> String {
+        if self.is_running() {
+            customization.format_running(&self.duration())
+        } else {
+            customization
+                .format_duration(&customization.round(&self.duration()))
+        }
+    }
+
+    /// Whether the description or a tag contains `query`, ignoring case.
+    pub fn matches_filter(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self
+                .description
+                .as_ref()
+                .is_some_and(|d| d.to_lowercase().contains(&query))
+            || self.tags.iter().any(|t| t.to_lowercase().contains(&query))
+    }
+
+    /// Attach `tag` unless it is already there.
+    pub fn add_tag(&mut self, tag: &str) {
+        if !self.tags.iter().any(|t| t == tag) {
+            self.tags.push(tag.to_string());
+            // Stale ids would win over the names on the server.
+            self.tag_ids.clear();
+        }
+    }
+
+    pub fn remove_tag(&mut self, tag: &str) {
+        if self.tags.iter().any(|t| t == tag) {
+            self.tags.retain(|t| t != tag);
+            self.tag_ids.clear();
+        }
+    }
+
+    /// Replace all tags, dropping the ids that name the old ones.
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        if self.tags != tags {
+            self.tags = tags;
+            self.tag_ids.clear();
+        }
     }
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CreateTimeEntry {
+    billable: bool,
     created_with: String,
     description: Option<String>,
     duration: i64,
     start: DateTime<Local>,
     workspace_id: WorkspaceId,
     project_id: Option<ProjectId>,
+    tags: Vec<String>,
+    task_id: Option<u64>,
 }
 
 impl CreateTimeEntry {
@@ -165,18 +402,62 @@ impl CreateTimeEntry {
         description: Option<String>,
         workspace_id: WorkspaceId,
         project_id: Option<ProjectId>,
+        tags: Vec<String>,
     ) -> Self {
         Self {
+            billable: false,
             created_with: "ST-Toggl-Client".to_string(),
             description,
-            duration: -1,
+            duration: RUNNING_DURATION,
             start: Local::now(),
             workspace_id,
             project_id,
+            tags,
+            task_id: None,
+        }
+    }
+
+    /// Make it a finished entry instead of a running one.
+    pub fn with_times(self, start: DateTime<Local>, duration: i64) -> Self {
+        Self {
+            start,
+            duration,
+            ..self
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.duration < 0
+    }
+
+    /// Stop the entry before it is sent.
+    pub fn stop_at(&mut self, stop: DateTime<Local>) {
+        self.duration = (stop - self.start).num_seconds().max(0);
+    }
+
+    /// The entry as shown until the server has it, with [`LOCAL_ENTRY_ID`].
+    pub fn preview(&self) -> TimeEntry {
+        TimeEntry {
+            at: self.start.to_rfc3339(),
+            billable: self.billable,
+            description: self.description.clone(),
+            duration: self.duration,
+            id: LOCAL_ENTRY_ID,
+            permissions: None,
+            project_id: self.project_id,
+            start: self.start,
+            stop: (!self.is_running())
+                .then(|| self.start + Duration::seconds(self.duration)),
+            server_deleted_at: None,
+            tag_ids: vec![],
+            tags: self.tags.clone(),
+            task_id: self.task_id,
+            user_id: 0,
+            workspace_id: self.workspace_id,
         }
     }
 
-    pub async fn create(&self, client: &Client) -> NetResult<()> {
+    pub async fn create(&self, client: &Client) -> NetResult<TimeEntry> {
         debug!("Creating a time entry...");
         let mut res = client
             .post(
@@ -192,7 +473,72 @@ impl CreateTimeEntry {
             .body_json(&self)?
             .send()
             .await?;
-        Client::check_status(&mut res).await
+        Client::check_status(&mut res).await?;
+        res.body_json().await
+    }
+}
+
+/// Id of an entry created offline, until the server assigns a real one.
+pub const LOCAL_ENTRY_ID: u64 = 0;
+
+/// A change that could not reach the server, kept to send once back online.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum PendingChange {
+    Create(CreateTimeEntry),
+    Update(TimeEntry),
+    Delete(TimeEntry),
+}
+
+impl PendingChange {
+    pub async fn send(&self, client: &Client) -> NetResult<()> {
+        match self {
+            Self::Create(entry) => entry.create(client).await.map(|_| ()),
+            Self::Update(entry) => entry.save(client).await.map(|_| ()),
+            Self::Delete(entry) => entry.clone().delete(client).await,
+        }
+    }
+
+    /// Entry the change is about, [`LOCAL_ENTRY_ID`] for new ones.
+    pub fn entry_id(&self) -> u64 {
+        match self {
+            Self::Create(_) => LOCAL_ENTRY_ID,
+            Self::Update(entry) | Self::Delete(entry) => entry.id,
+        }
+    }
+}
+
+/// Local marker for an entry, independent of its project color.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum EntryFlag {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+}
+
+impl EntryFlag {
+    pub const VALUES: [Self; 4] =
+        [Self::Red, Self::Yellow, Self::Green, Self::Blue];
+
+    /// The flag after `current`, wrapping around to no flag.
+    pub fn next(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Self::VALUES[0]),
+            Some(flag) => {
+                let i = Self::VALUES.iter().position(|&f| f == flag)?;
+                Self::VALUES.get(i + 1).copied()
+            }
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn color(self) -> Color {
+        match self {
+            Self::Red => Color::from_rgb8(0xe5, 0x39, 0x35),
+            Self::Yellow => Color::from_rgb8(0xfd, 0xd8, 0x35),
+            Self::Green => Color::from_rgb8(0x43, 0xa0, 0x47),
+            Self::Blue => Color::from_rgb8(0x1e, 0x88, 0xe5),
+        }
     }
 }
 
@@ -201,36 +547,86 @@ pub enum TimeEntryMessage {
     Edit(u64),
     EditRunning,
     StopRunning,
+    /// Move the running entry's start by one nudge step; the sign gives the
+    /// direction.
+    NudgeRunningStart(i32),
+    StartedAgoEdited(String),
+    /// Move the running entry's start to the typed time ago.
+    SubmitStartedAgo,
+    /// Show or hide the input to stop the running entry in the past.
+    ToggleStopAt,
+    StopAtEdited(String),
+    SubmitStopAt,
     Duplicate(Box<TimeEntry>),
+    ToggleGroup(u64),
+    /// Move the entry to its next local flag.
+    CycleFlag(u64),
+    OpenContextMenu(u64),
+    CloseContextMenu,
+    /// Create a stopped copy of the entry with the same times.
+    Copy(Box<TimeEntry>),
+    Delete(Box<TimeEntry>),
+    CopyDetails(u64),
+    OpenInToggl,
+    SetFlag(u64, Option<EntryFlag>),
+    SetRunningProject(Option<ProjectId>),
+    RunningTagsEdited(String),
+    /// Save the typed comma-separated tags on the running entry.
+    SubmitRunningTags,
 }
 
+#[cfg(feature = "gui")]
 impl TimeEntry {
-    pub fn view(&self, projects: &[Project]) -> Element<TimeEntryMessage> {
+    pub fn view(
+        &self,
+        projects: &[Project],
+        customization: &Customization,
+        flag: Option<EntryFlag>,
+    ) -> Element<TimeEntryMessage> {
         let project = projects.iter().find(|p| Some(p.id) == self.project_id);
         let name = self
             .description
             .clone()
             .unwrap_or("<NO DESCRIPTION>".to_string());
-        let project_badge = if let Some(project) = project {
-            let color = Color::parse(&project.color)
-                .expect("Project color must be valid");
-            badge::Badge::new(text(project.name.clone()).size(12)).style(
-                move |_, _| badge::Style {
-                    background: color.into(),
-                    ..badge::Style::default()
-                },
-            )
-        } else {
-            badge(text("No project".to_string()).size(12))
-                .style(iced_aw::style::badge::light)
-        };
-        button(
+        let columns = customization.columns;
+        let details =
+            row![]
+                .push_maybe(
+                    columns
+                        .project
+                        .then(|| project_badge(project, columns.client)),
+                )
+                .push_maybe((columns.tags && !self.tags.is_empty()).then(
+                    || text(format!("#{}", self.tags.join(" #"))).size(12),
+                ))
+                .push_maybe(
+                    (columns.billable && self.billable)
+                        .then(|| text("$").size(12).style(text::success)),
+                )
+                .spacing(5)
+                .align_y(Vertical::Center);
+        let entry = button(
             row![
+                button(text(if flag.is_some() {
+                    "\u{25CF}"
+                } else {
+                    "\u{25CB}"
+                }))
+                .on_press(TimeEntryMessage::CycleFlag(self.id))
+                .style(move |theme, status| {
+                    let mut style = button::text(theme, status);
+                    if let Some(flag) = flag {
+                        style.text_color = flag.color();
+                    }
+                    style
+                })
+                .padding(0)
+                .width(Length::Shrink),
                 column![
                     text(name)
                         .width(Length::Fill)
                         .wrapping(text::Wrapping::None),
-                    project_badge
+                    details
                 ],
                 button("+")
                     .style(button::primary)
@@ -238,7 +634,8 @@ impl TimeEntry {
                         self.clone()
                     )))
                     .width(Length::Shrink),
-                text(self.duration_string()).width(Length::Fixed(60f32))
+                text(self.duration_string(customization))
+                    .width(Length::Fixed(60f32))
             ]
             .spacing(10)
             .padding(iced::Padding {
@@ -249,37 +646,224 @@ impl TimeEntry {
         )
         .on_press(TimeEntryMessage::Edit(self.id))
         .clip(true)
+        .style(button::text);
+        mouse_area(entry)
+            .on_right_press(TimeEntryMessage::OpenContextMenu(self.id))
+            .into()
+    }
+
+    /// Actions shown under an entry after a right click.
+    pub fn view_context_menu(
+        &self,
+        flag: Option<EntryFlag>,
+    ) -> Element<TimeEntryMessage> {
+        let action = |label, message| {
+            button(text(label).size(12))
+                .on_press(message)
+                .style(button::secondary)
+        };
+        let flags = EntryFlag::VALUES.iter().map(|&f| {
+            button(text("\u{25CF}").size(12))
+                .on_press_maybe(
+                    (flag != Some(f))
+                        .then_some(TimeEntryMessage::SetFlag(self.id, Some(f))),
+                )
+                .style(move |theme, status| button::Style {
+                    text_color: f.color(),
+                    ..button::text(theme, status)
+                })
+                .into()
+        });
+        row![
+            action("Edit", TimeEntryMessage::Edit(self.id)),
+            action(
+                "Continue",
+                TimeEntryMessage::Duplicate(Box::new(self.clone()))
+            ),
+            action("Duplicate", TimeEntryMessage::Copy(Box::new(self.clone()))),
+            action("Copy details", TimeEntryMessage::CopyDetails(self.id)),
+            action("Open in Toggl", TimeEntryMessage::OpenInToggl),
+            button(text("Delete").size(12))
+                .on_press(TimeEntryMessage::Delete(Box::new(self.clone())))
+                .style(button::danger),
+        ]
+        .push(
+            row(flags).push(action(
+                "No flag",
+                TimeEntryMessage::SetFlag(self.id, None),
+            )),
+        )
+        .push(
+            button(text("X").size(12))
+                .on_press(TimeEntryMessage::CloseContextMenu)
+                .style(button::text),
+        )
+        .spacing(5)
+        .padding([5, 10])
+        .wrap()
+        .into()
+    }
+
+    /// Render a collapsible summary of several similar entries.
+    pub fn view_group<'a>(
+        entries: &[&'a TimeEntry],
+        projects: &[Project],
+        customization: &Customization,
+        expanded: bool,
+    ) -> Element<'a, TimeEntryMessage> {
+        let first = entries[0];
+        let project = projects.iter().find(|p| Some(p.id) == first.project_id);
+        let name = first
+            .description
+            .clone()
+            .unwrap_or("<NO DESCRIPTION>".to_string());
+        let total = entries
+            .iter()
+            .map(|e| e.duration())
+            .fold(Duration::zero(), |acc, d| acc + d);
+        button(
+            row![
+                column![
+                    text(format!("{name} \u{d7}{}", entries.len()))
+                        .width(Length::Fill)
+                        .wrapping(text::Wrapping::None),
+                    project_badge(project, customization.columns.client)
+                ],
+                text(if expanded { "[-]" } else { "[+]" }).size(12),
+                text(customization.format_duration(&total))
+                    .width(Length::Fixed(60f32))
+            ]
+            .spacing(10)
+            .padding(iced::Padding {
+                right: 10f32,
+                ..iced::Padding::default()
+            })
+            .align_y(Vertical::Center),
+        )
+        .on_press(TimeEntryMessage::ToggleGroup(first.id))
+        .clip(true)
         .style(button::text)
         .into()
     }
 
-    pub fn view_running(&self) -> Element<TimeEntryMessage> {
+    /// `tags` is the text of the tags input, `None` until it is edited.
+    pub fn view_running<'a>(
+        &'a self,
+        projects: &'a [Project],
+        customization: &Customization,
+        started_ago: &str,
+        stop_at: Option<&'a str>,
+        tags: Option<&'a str>,
+    ) -> Element<'a, TimeEntryMessage> {
         let name = self
             .description
             .clone()
             .unwrap_or("<NO DESCRIPTION>".to_string());
-        container(
+        let stop_at_row = stop_at.map(|stop_at| {
             row![
-                button(text(name).wrapping(text::Wrapping::None))
-                    .width(Length::Fill)
-                    .style(|_, _| button::Style {
-                        text_color: Color::WHITE,
-                        ..button::Style::default()
-                    })
-                    .on_press(TimeEntryMessage::EditRunning)
-                    .clip(true),
-                text(self.duration_string()).width(Length::Fixed(60f32)),
-                button("Stop")
+                text("Stopped at").size(12),
+                text_input("HH:MM", stop_at)
+                    .id("stop-at-input")
+                    .on_input(TimeEntryMessage::StopAtEdited)
+                    .on_submit(TimeEntryMessage::SubmitStopAt)
+                    .size(12)
+                    .width(Length::Fixed(80f32)),
+                button(text("Stop").size(12))
                     .style(button::primary)
-                    .on_press(TimeEntryMessage::StopRunning)
+                    .on_press(TimeEntryMessage::SubmitStopAt),
+                button(text("Cancel").size(12))
+                    .style(button::secondary)
+                    .on_press(TimeEntryMessage::ToggleStopAt),
+            ]
+            .spacing(10)
+            .padding([5, 10])
+            .align_y(Vertical::Center)
+        });
+        let project = projects
+            .iter()
+            .find(|p| Some(p.id) == self.project_id)
+            .cloned();
+        let details_row = row![
+            pick_list(
+                std::iter::once(MaybeProject::None)
+                    .chain(
+                        projects
+                            .iter()
+                            .filter(|p| p.fits_workspace(self.workspace_id))
+                            .cloned()
+                            .map(MaybeProject::Some)
+                    )
+                    .collect::<Vec<_>>(),
+                Some(MaybeProject::from(project)),
+                |p| TimeEntryMessage::SetRunningProject(
+                    Option::<Project>::from(p).map(|p| p.id)
+                ),
+            )
+            .text_size(12),
+            text_input(
+                "tag, another tag",
+                &tags.map_or_else(|| self.tags.join(", "), str::to_string)
+            )
+            .on_input(TimeEntryMessage::RunningTagsEdited)
+            .on_submit(TimeEntryMessage::SubmitRunningTags)
+            .size(12),
+        ]
+        .spacing(10)
+        .padding([5, 10])
+        .align_y(Vertical::Center);
+        container(
+            column![row![
+                button(
+                    column![text(name).wrapping(text::Wrapping::None)]
+                        .push_maybe(
+                            started_on_label(
+                                &self.start,
+                                Local::now(),
+                                customization
+                            )
+                            .map(|label| text(label).size(12))
+                        )
+                )
+                .width(Length::Fill)
+                .style(|_, _| button::Style {
+                    text_color: Color::WHITE,
+                    ..button::Style::default()
+                })
+                .on_press(TimeEntryMessage::EditRunning)
+                .clip(true),
+                text_input("started ago", started_ago)
+                    .on_input(TimeEntryMessage::StartedAgoEdited)
+                    .on_submit(TimeEntryMessage::SubmitStartedAgo)
+                    .size(12)
+                    .width(Length::Fixed(80f32)),
+                button(text("-").center())
+                    .on_press(TimeEntryMessage::NudgeRunningStart(-1))
+                    .width(Length::Fixed(28f32)),
+                button(text("+").center())
+                    .on_press(TimeEntryMessage::NudgeRunningStart(1))
+                    .width(Length::Fixed(28f32)),
+                text(self.duration_string(customization))
                     .width(Length::Fixed(60f32)),
+                row![
+                    button("Stop")
+                        .style(button::primary)
+                        .on_press(TimeEntryMessage::StopRunning)
+                        .width(Length::Fixed(60f32)),
+                    button(text("\u{25BE}").center())
+                        .style(button::primary)
+                        .on_press(TimeEntryMessage::ToggleStopAt)
+                        .width(Length::Fixed(24f32)),
+                ]
+                .spacing(1),
             ]
             .spacing(10)
             .padding(iced::Padding {
                 right: 10f32,
                 ..iced::Padding::default()
             })
-            .align_y(Vertical::Center),
+            .align_y(Vertical::Center)]
+            .push(details_row)
+            .push_maybe(stop_at_row),
         )
         .style(|_| container::Style {
             background: Some(iced::color!(0x161616).into()),
@@ -290,7 +874,16 @@ impl TimeEntry {
     }
 }
 
-fn duration_to_hms(duration: &Duration) -> String {
+fn already_stopped() -> surf::Error {
+    surf::Error::from_str(surf::StatusCode::Conflict, "Entry already stopped.")
+}
+
+/// Whether stopping failed because the entry is not running anymore.
+pub fn is_already_stopped(error: &surf::Error) -> bool {
+    error.status() == surf::StatusCode::Conflict
+}
+
+pub fn duration_to_hms(duration: &Duration) -> String {
     let total_seconds = duration.num_seconds();
     let seconds = total_seconds % 60;
     let minutes = (total_seconds / 60) % 60;
@@ -298,10 +891,768 @@ fn duration_to_hms(duration: &Duration) -> String {
     format!("{}:{:0>2}:{:0>2}", hours, minutes, seconds)
 }
 
+/// Parse "h:mm:ss" or "h:mm", the way [`duration_to_hms`] writes it.
+/// Returned by every duration parser for a duration past its limit.
+pub const DURATION_TOO_LONG: &str = "The duration is too long";
+
+pub fn parse_hms(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("Can't read '{text}' as a duration, try 1:30:00");
+    let parts = text
+        .trim()
+        .split(':')
+        .map(|part| part.trim().parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    let (hours, minutes, seconds) = match parts[..] {
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        [hours, minutes] => (hours, minutes, 0),
+        _ => return Err(invalid()),
+    };
+    if hours < 0 || !(0..60).contains(&minutes) || !(0..60).contains(&seconds) {
+        return Err(invalid());
+    }
+    Duration::try_hours(hours)
+        .and_then(|h| h.checked_add(&Duration::minutes(minutes)))
+        .and_then(|d| d.checked_add(&Duration::seconds(seconds)))
+        .ok_or_else(|| DURATION_TOO_LONG.to_string())
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InvalidTimes {
+    BlankStart,
+    StartInFuture,
+    StopBeforeStart,
+}
+
+impl std::fmt::Display for InvalidTimes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            InvalidTimes::BlankStart => "Start cannot be blank",
+            InvalidTimes::StartInFuture => "Start cannot be in the future",
+            InvalidTimes::StopBeforeStart => "Stop must come after start",
+        };
+        f.write_str(repr)
+    }
+}
+
+/// Check the start/stop pair of an entry and compute its duration.
+///
+/// Returns the start and the duration in seconds, [`RUNNING_DURATION`] for a
+/// running entry.
+/// All edit paths must go through this to keep the invariants in one place.
+pub fn validate_times(
+    start: Option<DateTime<Local>>,
+    stop: Option<DateTime<Local>>,
+    now: DateTime<Local>,
+) -> Result<(DateTime<Local>, i64), InvalidTimes> {
+    let start = start.ok_or(InvalidTimes::BlankStart)?;
+    if start > now {
+        return Err(InvalidTimes::StartInFuture);
+    }
+    match stop {
+        None => Ok((start, RUNNING_DURATION)),
+        Some(stop) if stop < start => Err(InvalidTimes::StopBeforeStart),
+        Some(stop) => Ok((start, (stop - start).num_seconds())),
+    }
+}
+
+/// Resolve a typed wall-clock stop time for an entry running since `start`.
+///
+/// The time is taken from today, or from yesterday if it is still ahead, so
+/// that an entry running past midnight can be stopped before it.
+pub fn past_stop_time(
+    time: NaiveTime,
+    start: DateTime<Local>,
+    now: DateTime<Local>,
+) -> Result<DateTime<Local>, InvalidTimes> {
+    let mut stop = now + (time - now.time());
+    if stop > now {
+        stop -= Duration::days(1);
+    }
+    validate_times(Some(start), Some(stop), now).map(|_| stop)
+}
+
+/// Longest elapsed time accepted by [`parse_elapsed`].
+const MAX_ELAPSED: Duration = Duration::hours(24);
+
+/// Parse elapsed time like "20m", "1h 30m", "1:30" or "20" (minutes), up to
+/// [`MAX_ELAPSED`].
+pub fn parse_elapsed(text: &str) -> Result<Duration, String> {
+    let invalid =
+        || format!("Can't read '{text}' as a duration, try 20m or 1h 30m");
+    let too_long = || DURATION_TOO_LONG.to_string();
+    let text = text.trim().to_lowercase();
+    let elapsed = if let Some((hours, minutes)) = text.split_once(':') {
+        let hours: i64 = hours.trim().parse().map_err(|_| invalid())?;
+        let minutes: i64 = minutes.trim().parse().map_err(|_| invalid())?;
+        if !(0..60).contains(&minutes) {
+            return Err(invalid());
+        }
+        Duration::try_hours(hours)
+            .and_then(|h| h.checked_add(&Duration::minutes(minutes)))
+            .ok_or_else(too_long)?
+    } else if let Ok(minutes) = text.parse::<i64>() {
+        Duration::try_minutes(minutes).ok_or_else(too_long)?
+    } else {
+        let mut total = Duration::zero();
+        let mut rest = text.as_str();
+        while !rest.trim().is_empty() {
+            rest = rest.trim_start();
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(invalid)?;
+            let value: i64 = rest[..digits].parse().map_err(|_| invalid())?;
+            rest = &rest[digits..];
+            let unit_len = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            let part = match &rest[..unit_len] {
+                "h" | "hr" | "hrs" | "hour" | "hours" => {
+                    Duration::try_hours(value)
+                }
+                "m" | "min" | "mins" | "minute" | "minutes" => {
+                    Duration::try_minutes(value)
+                }
+                _ => return Err(invalid()),
+            };
+            total = part
+                .and_then(|part| total.checked_add(&part))
+                .ok_or_else(too_long)?;
+            rest = &rest[unit_len..];
+        }
+        total
+    };
+    if elapsed <= Duration::zero() {
+        Err("The duration must be positive".to_string())
+    } else if elapsed > MAX_ELAPSED {
+        Err(too_long())
+    } else {
+        Ok(elapsed)
+    }
+}
+
+/// Start and duration of a stopped entry ending `now` that lasted `text`,
+/// parsed with [`parse_elapsed`].
+pub fn completed_times(
+    text: &str,
+    now: DateTime<Local>,
+) -> Result<(DateTime<Local>, i64), String> {
+    let elapsed = parse_elapsed(text)?;
+    Ok((now - elapsed, elapsed.num_seconds()))
+}
+
+/// Note like "started yesterday 23:10" for entries that started before today.
+pub fn started_on_label(
+    start: &DateTime<Local>,
+    now: DateTime<Local>,
+    customization: &Customization,
+) -> Option<String> {
+    let day = start.date_naive();
+    let today = now.date_naive();
+    if day >= today {
+        return None;
+    }
+    let day = if today.pred_opt() == Some(day) {
+        "yesterday".to_string()
+    } else {
+        customization.format_date(&day)
+    };
+    Some(format!(
+        "started {day} {}",
+        customization.format_time(start)
+    ))
+}
+
+/// Sort entries newest first, breaking ties by id so that the order does not
+/// change between reloads.
+pub fn sort_entries(entries: &mut [TimeEntry]) {
+    entries.sort_by_key(|e| std::cmp::Reverse((e.start, e.id)));
+}
+
+/// Split entries into runs of consecutive entries with the same description
+/// and project.
+pub fn group_similar<'a>(
+    entries: impl IntoIterator<Item = &'a TimeEntry>,
+) -> Vec<Vec<&'a TimeEntry>> {
+    entries
+        .into_iter()
+        .chunk_by(|e| (e.description.clone(), e.project_id))
+        .into_iter()
+        .map(|(_, group)| group.collect())
+        .collect()
+}
+
+#[cfg(feature = "gui")]
+/// Colored project name, as `Client / Project` if `with_client`.
+fn project_badge<'a>(
+    project: Option<&Project>,
+    with_client: bool,
+) -> badge::Badge<'a, TimeEntryMessage, iced::Theme, iced::Renderer> {
+    if let Some(project) = project {
+        let color =
+            Color::parse(&project.color).expect("Project color must be valid");
+        let label = if with_client {
+            project.to_string()
+        } else {
+            project.name.clone()
+        };
+        badge::Badge::new(text(label).size(12)).style(move |_, _| {
+            badge::Style {
+                background: color.into(),
+                ..badge::Style::default()
+            }
+        })
+    } else {
+        badge(text("No project".to_string()).size(12))
+            .style(iced_aw::style::badge::light)
+    }
+}
+
+/// Entry for tests, stopped at `stop` or running if it is `None`.
+#[cfg(any(test, feature = "test-util"))]
+pub fn test_entry(
+    start: DateTime<Local>,
+    stop: Option<DateTime<Local>>,
+    project_id: Option<u64>,
+) -> TimeEntry {
+    serde_json::from_value(serde_json::json!({
+        "at": "2024-01-01T00:00:00Z",
+        "billable": false,
+        "description": "Test",
+        "duration": stop.map_or(RUNNING_DURATION, |stop| {
+            (stop - start).num_seconds()
+        }),
+        "id": 1,
+        "permissions": null,
+        "project_id": project_id,
+        "start": start,
+        "stop": stop,
+        "server_deleted_at": null,
+        "tag_ids": [],
+        "tags": [],
+        "task_id": null,
+        "user_id": 1,
+        "workspace_id": 1,
+    }))
+    .expect("Valid entry")
+}
+
+/// Time left until the next whole wall-clock second.
+///
+/// Entries start on whole seconds, so ticking right after the boundary keeps
+/// the displayed durations advancing by exactly one second.
+pub fn until_next_second(now: DateTime<Local>) -> std::time::Duration {
+    // Leap seconds report more than a billion nanoseconds.
+    let nanos = now.timestamp_subsec_nanos().min(999_999_999);
+    std::time::Duration::from_nanos(u64::from(1_000_000_000 - nanos))
+}
+
+/// Time left until an entry running since `start` completes another minute,
+/// or until the next wall-clock minute without one.
+pub fn until_next_minute(
+    now: DateTime<Local>,
+    start: Option<DateTime<Local>>,
+) -> std::time::Duration {
+    let offset = start.map_or(0, |start| start.second().min(59));
+    // Seconds into the current minute of the running duration.
+    let elapsed = (now.second().min(59) + 60 - offset) % 60;
+    std::time::Duration::from_secs(u64::from(59 - elapsed))
+        + until_next_second(now)
+}
+
+/// Beginning (Monday midnight) of the week containing `now`.
+pub fn to_start_of_week(now: DateTime<Local>) -> DateTime<Local> {
+    let monday = now.date_naive()
+        - Duration::days(now.weekday().num_days_from_monday().into());
+    monday
+        .and_time(NaiveTime::MIN)
+        .and_local_timezone(Local)
+        .earliest()
+        .unwrap_or(now)
+}
+
+/// Total time tracked between `start` and `end`, which must not be in the
+/// future.
+///
+/// Only the part of each entry inside the interval counts; a running entry
+/// is assumed to last until `end`.
+pub fn total_between<'a>(
+    entries: impl IntoIterator<Item = &'a TimeEntry>,
+    running_entry: Option<&'a TimeEntry>,
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+) -> Duration {
+    entries
+        .into_iter()
+        .chain(running_entry)
+        .filter_map(|e| {
+            let stop = e.stop.unwrap_or(end).min(end);
+            let start = e.start.max(start);
+            (stop > start).then(|| stop - start)
+        })
+        .fold(Duration::zero(), |acc, d| acc + d)
+}
+
+pub fn format_duration(duration: &Duration, format: DurationFormat) -> String {
+    // A negative duration only comes from bad data, e.g. the running
+    // sentinel or a clock jump: show nothing tracked rather than "-1:00:-1".
+    let duration = &(*duration).max(Duration::zero());
+    match format {
+        DurationFormat::Hms => duration_to_hms(duration),
+        DurationFormat::HoursMinutes => {
+            let total_minutes = duration.num_minutes();
+            format!("{}h {:0>2}m", total_minutes / 60, total_minutes % 60)
+        }
+        DurationFormat::Decimal => {
+            format!("{:.2}h", duration.num_seconds() as f64 / 3600.0)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::TimeEntry;
+    use chrono::Duration;
+
+    use chrono::{Local, TimeZone, Timelike};
+
+    use super::{
+        completed_times, duration_to_hms, format_duration, group_similar,
+        is_already_stopped, parse_elapsed, parse_hms, past_stop_time,
+        sort_entries, started_on_label, test_entry, to_start_of_week,
+        total_between, until_next_minute, until_next_second, validate_times,
+        EntryFlag, InvalidTimes, TimeEntry, DURATION_TOO_LONG,
+        RUNNING_DURATION,
+    };
     use crate::client::Client;
+    use crate::customization::{Customization, DurationFormat};
+
+    #[test]
+    fn test_add_remove_tag() {
+        let now = Local::now();
+        let mut entry = test_entry(now, None, None);
+        entry.tag_ids = vec![7];
+        entry.tags = vec!["Old".to_string()];
+        entry.add_tag("Old");
+        assert_eq!(entry.tag_ids, vec![7]);
+        entry.add_tag("New");
+        assert_eq!(entry.tags, vec!["Old", "New"]);
+        assert!(entry.tag_ids.is_empty());
+        entry.remove_tag("Missing");
+        entry.remove_tag("Old");
+        assert_eq!(entry.tags, vec!["New"]);
+    }
+
+    #[test]
+    fn test_set_tags() {
+        let mut entry = test_entry(Local::now(), None, None);
+        entry.tag_ids = vec![7];
+        entry.tags = vec!["Old".to_string()];
+        entry.set_tags(vec!["Old".to_string()]);
+        assert_eq!(entry.tag_ids, vec![7]);
+        entry.set_tags(vec!["New".to_string()]);
+        assert_eq!(entry.tags, vec!["New"]);
+        assert!(entry.tag_ids.is_empty());
+    }
+
+    #[test]
+    fn test_details() {
+        let start = Local.with_ymd_and_hms(2024, 6, 3, 9, 0, 0).unwrap();
+        let mut entry = test_entry(
+            start,
+            Some(start + chrono::Duration::minutes(90)),
+            None,
+        );
+        entry.tags = vec!["a".to_string(), "b".to_string()];
+        let details = entry.details(Some("Work"), &Customization::default());
+        assert!(
+            details.ends_with("Test [Work] #a #b (1:30:00)"),
+            "{details}"
+        );
+        entry.description = None;
+        entry.tags.clear();
+        let details = entry.details(None, &Customization::default());
+        assert!(details.ends_with("<NO DESCRIPTION> (1:30:00)"), "{details}");
+    }
+
+    #[test]
+    fn test_same_content() {
+        let start = Local.with_ymd_and_hms(2024, 6, 3, 9, 0, 0).unwrap();
+        let mut entry =
+            test_entry(start, Some(start + chrono::Duration::hours(1)), None);
+        entry.tags = vec!["a".to_string(), "b".to_string()];
+        let mut server = entry.clone();
+        // Server-side bookkeeping and tag order don't matter.
+        server.tags.reverse();
+        server.tag_ids = vec![1, 2];
+        assert!(entry.same_content(&server));
+        server.description = Some("Changed elsewhere".to_string());
+        assert!(!entry.same_content(&server));
+    }
+
+    #[test]
+    fn test_matches_filter() {
+        let start = Local.with_ymd_and_hms(2024, 6, 3, 9, 0, 0).unwrap();
+        let mut entry = test_entry(start, None, None);
+        entry.tags = vec!["Meeting".to_string()];
+        assert!(entry.matches_filter(" "));
+        assert!(entry.matches_filter("tES"));
+        assert!(entry.matches_filter("meet"));
+        assert!(!entry.matches_filter("review"));
+        entry.description = None;
+        assert!(!entry.matches_filter("test"));
+    }
+
+    #[test]
+    fn test_entry_flag_cycle() {
+        let mut flag = None;
+        let mut seen = vec![];
+        for _ in 0..=EntryFlag::VALUES.len() {
+            flag = EntryFlag::next(flag);
+            seen.push(flag);
+        }
+        assert_eq!(
+            seen,
+            vec![
+                Some(EntryFlag::Red),
+                Some(EntryFlag::Yellow),
+                Some(EntryFlag::Green),
+                Some(EntryFlag::Blue),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_running() {
+        let now = Local::now();
+        let start = now - chrono::Duration::hours(1);
+        let running = test_entry(start, None, None);
+        assert!(running.is_running());
+        assert_eq!(running.running_since(), Some(start));
+        let stopped = test_entry(start, Some(now), None);
+        assert!(!stopped.is_running());
+        assert_eq!(stopped.running_since(), None);
+        // Any negative duration counts, not just the canonical `-1`.
+        let legacy = TimeEntry {
+            duration: -start.timestamp(),
+            ..stopped
+        };
+        assert!(legacy.is_running());
+    }
+
+    #[test]
+    fn test_past_stop_time() {
+        let now = Local.with_ymd_and_hms(2024, 6, 10, 12, 0, 0).unwrap();
+        let start = Local.with_ymd_and_hms(2024, 6, 9, 22, 0, 0).unwrap();
+        let at = |h, m| chrono::NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert_eq!(
+            past_stop_time(at(11, 50), start, now),
+            Ok(Local.with_ymd_and_hms(2024, 6, 10, 11, 50, 0).unwrap())
+        );
+        // Still ahead today, so it must be yesterday.
+        assert_eq!(
+            past_stop_time(at(23, 0), start, now),
+            Ok(Local.with_ymd_and_hms(2024, 6, 9, 23, 0, 0).unwrap())
+        );
+        assert_eq!(
+            past_stop_time(at(21, 0), start, now),
+            Err(InvalidTimes::StopBeforeStart)
+        );
+    }
+
+    #[test]
+    fn test_until_next_second() {
+        let now = Local::now().with_nanosecond(250_000_000).unwrap();
+        assert_eq!(
+            until_next_second(now),
+            std::time::Duration::from_millis(750)
+        );
+        let now = now.with_nanosecond(0).unwrap();
+        assert_eq!(until_next_second(now), std::time::Duration::from_secs(1));
+        let now = now.with_second(30).unwrap();
+        assert_eq!(
+            until_next_minute(now, None),
+            std::time::Duration::from_secs(30)
+        );
+        // Started at hh:mm:40, the shown minutes go up at :40.
+        let start = Some(now.with_second(40).unwrap() - Duration::hours(1));
+        assert_eq!(
+            until_next_minute(now, start),
+            std::time::Duration::from_secs(10)
+        );
+        let now = now.with_second(45).unwrap();
+        assert_eq!(
+            until_next_minute(now, start),
+            std::time::Duration::from_secs(55)
+        );
+    }
+
+    #[test]
+    fn test_group_similar() {
+        let now = Local::now();
+        let mut entries: Vec<_> = (0..5)
+            .map(|i| {
+                let mut e =
+                    test_entry(now - Duration::hours(i), Some(now), None);
+                e.id = i as u64;
+                e
+            })
+            .collect();
+        entries[2].description = Some("Other".to_string());
+        let groups = group_similar(&entries);
+        let ids: Vec<Vec<u64>> = groups
+            .iter()
+            .map(|g| g.iter().map(|e| e.id).collect())
+            .collect();
+        assert_eq!(ids, vec![vec![0, 1], vec![2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn test_validate_times() {
+        let now = Local::now();
+        let start = now - Duration::hours(1);
+        assert_eq!(
+            validate_times(None, Some(now), now),
+            Err(InvalidTimes::BlankStart)
+        );
+        assert_eq!(
+            validate_times(Some(now + Duration::minutes(1)), None, now),
+            Err(InvalidTimes::StartInFuture)
+        );
+        assert_eq!(
+            validate_times(
+                Some(start),
+                Some(start - Duration::seconds(1)),
+                now
+            ),
+            Err(InvalidTimes::StopBeforeStart)
+        );
+        assert_eq!(
+            validate_times(Some(start), None, now),
+            Ok((start, RUNNING_DURATION))
+        );
+        assert_eq!(
+            validate_times(Some(start), Some(now), now),
+            Ok((start, 3600))
+        );
+    }
+
+    #[test]
+    fn test_week_total_clamps_running_entry() {
+        let now = Local::now();
+        let week_start = to_start_of_week(now);
+        let running = test_entry(week_start - Duration::hours(2), None, None);
+        assert_eq!(
+            total_between(&[], Some(&running), week_start, now),
+            now - week_start
+        );
+    }
+
+    #[test]
+    fn test_week_total_skips_previous_weeks() {
+        let now = Local::now();
+        let week_start = to_start_of_week(now);
+        let entries = [
+            test_entry(now - Duration::seconds(10), Some(now), None),
+            test_entry(
+                week_start - Duration::hours(3),
+                Some(week_start - Duration::hours(1)),
+                None,
+            ),
+        ];
+        assert_eq!(
+            total_between(&entries, None, week_start, now),
+            Duration::seconds(10)
+        );
+    }
+
+    #[test]
+    fn test_total_between_custom_start() {
+        let now = Local::now();
+        let start = now - Duration::days(10);
+        let entries = [
+            test_entry(
+                now - Duration::days(9),
+                Some(now - Duration::days(8)),
+                None,
+            ),
+            test_entry(
+                start - Duration::hours(1),
+                Some(start + Duration::hours(1)),
+                None,
+            ),
+            test_entry(
+                start - Duration::days(1),
+                Some(start - Duration::hours(2)),
+                None,
+            ),
+        ];
+        assert_eq!(
+            total_between(&entries, None, start, now),
+            Duration::days(1) + Duration::hours(1)
+        );
+    }
+
+    #[test]
+    fn test_sort_entries_stable_ties() {
+        let now = Local::now();
+        let mut entries: Vec<_> = [3, 1, 2]
+            .into_iter()
+            .map(|id| TimeEntry {
+                id,
+                ..test_entry(now - Duration::hours(1), Some(now), None)
+            })
+            .chain(std::iter::once(TimeEntry {
+                id: 4,
+                ..test_entry(now - Duration::hours(2), Some(now), None)
+            }))
+            .collect();
+        sort_entries(&mut entries);
+        let ids: Vec<_> = entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, [3, 2, 1, 4]);
+        entries.reverse();
+        sort_entries(&mut entries);
+        let ids: Vec<_> = entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, [3, 2, 1, 4]);
+    }
+
+    #[test]
+    fn test_started_on_label() {
+        let customization = Customization::default();
+        let now = Local::now();
+        assert_eq!(started_on_label(&now, now, &customization), None);
+        let yesterday = now - Duration::days(1);
+        assert_eq!(
+            started_on_label(&yesterday, now, &customization),
+            Some(format!(
+                "started yesterday {}",
+                customization.format_time(&yesterday)
+            ))
+        );
+        let earlier = now - Duration::days(3);
+        let label = started_on_label(&earlier, now, &customization).unwrap();
+        assert!(label.starts_with(&format!(
+            "started {}",
+            customization.format_date(&earlier.date_naive())
+        )));
+    }
+
+    #[test]
+    fn test_parse_elapsed() {
+        assert_eq!(parse_elapsed("20m"), Ok(Duration::minutes(20)));
+        assert_eq!(parse_elapsed("20"), Ok(Duration::minutes(20)));
+        assert_eq!(parse_elapsed(" 1h 30m "), Ok(Duration::minutes(90)));
+        assert_eq!(parse_elapsed("1H30MIN"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_elapsed("1:05"), Ok(Duration::minutes(65)));
+        assert!(parse_elapsed("").is_err());
+        assert!(parse_elapsed("0m").is_err());
+        assert!(parse_elapsed("-5").is_err());
+        assert_eq!(parse_elapsed("25h"), Err(DURATION_TOO_LONG.to_string()));
+        assert!(parse_elapsed("1:75").is_err());
+        assert!(parse_elapsed("20 parsecs").is_err());
+        assert!(parse_elapsed("h").is_err());
+        let too_long = Err(DURATION_TOO_LONG.to_string());
+        assert_eq!(parse_elapsed("9999999999999999:00"), too_long);
+        assert_eq!(parse_elapsed("200000000000000000h"), too_long);
+        assert_eq!(parse_elapsed("9223372036854775807"), too_long);
+    }
+
+    #[test]
+    fn test_parse_hms() {
+        let duration = Duration::seconds(3 * 3600 + 5 * 60 + 9);
+        assert_eq!(parse_hms(&duration_to_hms(&duration)), Ok(duration));
+        assert_eq!(parse_hms(" 0:45 "), Ok(Duration::minutes(45)));
+        assert_eq!(parse_hms("26:00:00"), Ok(Duration::hours(26)));
+        assert!(parse_hms("").is_err());
+        assert!(parse_hms("45").is_err());
+        assert!(parse_hms("1:60").is_err());
+        assert!(parse_hms("-1:00:00").is_err());
+        assert!(parse_hms("1:00:00:00").is_err());
+        assert_eq!(
+            parse_hms("9999999999999999:00:00"),
+            Err(DURATION_TOO_LONG.to_string())
+        );
+    }
+
+    #[test]
+    fn test_completed_times() {
+        let now = Local.with_ymd_and_hms(2024, 6, 10, 12, 0, 0).unwrap();
+        let (start, duration) = completed_times("1:30", now).unwrap();
+        assert_eq!(start, now - Duration::minutes(90));
+        assert_eq!(duration, 90 * 60);
+        assert_eq!(completed_times("90m", now).unwrap().1, 90 * 60);
+        assert!(completed_times("-5", now).is_err());
+        assert!(completed_times("soon", now).is_err());
+        // Shown inline under the input, it must not panic.
+        assert_eq!(
+            completed_times("9999999999999999:00", now),
+            Err(DURATION_TOO_LONG.to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        let duration = Duration::seconds(3600 + 23 * 60 + 45);
+        assert_eq!(format_duration(&duration, DurationFormat::Hms), "1:23:45");
+        assert_eq!(
+            format_duration(&duration, DurationFormat::HoursMinutes),
+            "1h 23m"
+        );
+        assert_eq!(
+            format_duration(&duration, DurationFormat::Decimal),
+            "1.40h"
+        );
+
+        let short = Duration::seconds(5 * 60 + 59);
+        assert_eq!(format_duration(&short, DurationFormat::Hms), "0:05:59");
+        assert_eq!(
+            format_duration(&short, DurationFormat::HoursMinutes),
+            "0h 05m"
+        );
+        assert_eq!(format_duration(&short, DurationFormat::Decimal), "0.10h");
+    }
+
+    #[test]
+    fn test_format_duration_edge_cases() {
+        for negative in
+            [Duration::seconds(RUNNING_DURATION), Duration::hours(-3)]
+        {
+            assert_eq!(
+                format_duration(&negative, DurationFormat::Hms),
+                "0:00:00"
+            );
+            assert_eq!(
+                format_duration(&negative, DurationFormat::HoursMinutes),
+                "0h 00m"
+            );
+            assert_eq!(
+                format_duration(&negative, DurationFormat::Decimal),
+                "0.00h"
+            );
+        }
+        let huge = Duration::days(100 * 365) + Duration::seconds(61);
+        assert_eq!(format_duration(&huge, DurationFormat::Hms), "876000:01:01");
+        assert_eq!(
+            format_duration(&huge, DurationFormat::HoursMinutes),
+            "876000h 01m"
+        );
+        assert_eq!(
+            format_duration(&huge, DurationFormat::Decimal),
+            "876000.02h"
+        );
+    }
+
+    #[async_std::test]
+    async fn test_stop_stopped_entry() {
+        let now = Local::now();
+        let entry =
+            test_entry(now - chrono::Duration::hours(1), Some(now), None);
+        // Fails before any request is made.
+        let client = Client::from_api_token("unused");
+        let err = entry.stop(&client).await.unwrap_err();
+        assert!(is_already_stopped(&err));
+        assert_eq!(err.to_string(), "Entry already stopped.");
+    }
 
     #[async_std::test]
     async fn test_load_until_now() {
@@ -319,4 +1670,30 @@ mod test {
         assert_ne!(prev_entries.len(), 0);
         assert_ne!(prev_entries.first(), entries.last());
     }
+
+    #[async_std::test]
+    async fn test_continue_entry() {
+        let client = Client::from_email_password(
+            &std::env::var("TEST_EMAIL").expect("Please pass TEST_EMAIL"),
+            &std::env::var("TEST_PASSWORD").expect("Please pass TEST_PASSWORD"),
+        );
+        let entries = TimeEntry::load(None, &client).await.expect("Failed");
+        let source = entries
+            .iter()
+            .find(|e| !e.is_running())
+            .expect("Need at least one stopped entry");
+
+        let continued = source.continue_entry(&client).await.expect("Failed");
+        assert_ne!(continued.id, source.id);
+        assert!(continued.is_running());
+        assert!(continued.stop.is_none());
+        assert_eq!(continued.description, source.description);
+        assert_eq!(continued.project_id, source.project_id);
+        assert_eq!(continued.task_id, source.task_id);
+        assert_eq!(continued.tags, source.tags);
+        assert_eq!(continued.billable, source.billable);
+
+        continued.stop(&client).await.expect("Failed");
+        continued.delete(&client).await.expect("Failed");
+    }
 }
@@ -0,0 +1,176 @@
+//! Tray icon showing whether a timer runs, with quick actions.
+//!
+//! It speaks the StatusNotifierItem protocol, so it is only shown on Linux
+//! desktops hosting one, and only with the `tray` feature; elsewhere the
+//! subscription yields nothing.
+
+use iced::Subscription;
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(not(all(target_os = "linux", feature = "tray")), allow(dead_code))]
+pub enum TrayMessage {
+    StopRunning,
+    StartTimer,
+    /// Show the window with the new entry input focused.
+    NewEntry,
+    ToggleWindow,
+}
+
+#[cfg(all(target_os = "linux", feature = "tray"))]
+mod sni {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    use iced::futures::channel::mpsc;
+    use iced::futures::{SinkExt, StreamExt};
+    use ksni::menu::StandardItem;
+    use ksni::{Icon, MenuItem, TrayMethods};
+    use lazy_static::lazy_static;
+    use log::warn;
+
+    use super::TrayMessage;
+
+    lazy_static! {
+        static ref RUNNING_PIXMAP: Icon =
+            pixmap(include_bytes!("../assets/icon.png"));
+        static ref DEFAULT_PIXMAP: Icon =
+            pixmap(include_bytes!("../assets/icon-gray.png"));
+    }
+
+    static HANDLE: Mutex<Option<ksni::Handle<AppTray>>> = Mutex::new(None);
+    /// Last known timer state, for a tray that starts after it was set.
+    static RUNNING: AtomicBool = AtomicBool::new(false);
+
+    /// Convert a PNG to the ARGB pixmap the protocol expects.
+    fn pixmap(png: &[u8]) -> Icon {
+        let image = image::load_from_memory(png)
+            .expect("Icon must parse")
+            .into_rgba8();
+        let (width, height) = image.dimensions();
+        let data = image
+            .pixels()
+            .flat_map(|pixel| {
+                let [r, g, b, a] = pixel.0;
+                [a, r, g, b]
+            })
+            .collect();
+        Icon {
+            width: width as i32,
+            height: height as i32,
+            data,
+        }
+    }
+
+    struct AppTray {
+        running: bool,
+        sender: mpsc::UnboundedSender<TrayMessage>,
+    }
+
+    impl AppTray {
+        fn item(
+            label: &str,
+            enabled: bool,
+            message: TrayMessage,
+        ) -> MenuItem<Self> {
+            StandardItem {
+                label: label.to_string(),
+                enabled,
+                activate: Box::new(move |tray: &mut Self| {
+                    let _ = tray.sender.unbounded_send(message);
+                }),
+                ..StandardItem::default()
+            }
+            .into()
+        }
+    }
+
+    impl ksni::Tray for AppTray {
+        fn id(&self) -> String {
+            "toggl-tracker".to_string()
+        }
+
+        fn title(&self) -> String {
+            if self.running {
+                "Toggl Tracker (running)".to_string()
+            } else {
+                "Toggl Tracker".to_string()
+            }
+        }
+
+        fn icon_pixmap(&self) -> Vec<Icon> {
+            if self.running {
+                vec![RUNNING_PIXMAP.clone()]
+            } else {
+                vec![DEFAULT_PIXMAP.clone()]
+            }
+        }
+
+        fn activate(&mut self, _x: i32, _y: i32) {
+            let _ = self.sender.unbounded_send(TrayMessage::ToggleWindow);
+        }
+
+        fn menu(&self) -> Vec<MenuItem<Self>> {
+            vec![
+                Self::item(
+                    "Stop timer",
+                    self.running,
+                    TrayMessage::StopRunning,
+                ),
+                Self::item(
+                    "Start timer",
+                    !self.running,
+                    TrayMessage::StartTimer,
+                ),
+                Self::item("New entry...", true, TrayMessage::NewEntry),
+                MenuItem::Separator,
+                Self::item("Show/hide window", true, TrayMessage::ToggleWindow),
+            ]
+        }
+    }
+
+    pub fn events() -> impl iced::futures::Stream<Item = TrayMessage> {
+        iced::stream::channel(10, |mut output| async move {
+            let (sender, mut receiver) = mpsc::unbounded();
+            let tray = AppTray {
+                running: RUNNING.load(Ordering::Relaxed),
+                sender,
+            };
+            match tray.spawn().await {
+                Ok(handle) => {
+                    *HANDLE.lock().expect("Tray lock poisoned") = Some(handle)
+                }
+                Err(e) => {
+                    warn!("No tray icon: {e}");
+                    return;
+                }
+            }
+            while let Some(message) = receiver.next().await {
+                let _ = output.send(message).await;
+            }
+        })
+    }
+
+    pub async fn set_running(running: bool) {
+        RUNNING.store(running, Ordering::Relaxed);
+        let handle = HANDLE.lock().expect("Tray lock poisoned").clone();
+        if let Some(handle) = handle {
+            handle.update(|tray| tray.running = running).await;
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "tray"))]
+pub fn subscription() -> Subscription<TrayMessage> {
+    Subscription::run(sni::events)
+}
+
+#[cfg(all(target_os = "linux", feature = "tray"))]
+pub use sni::set_running;
+
+#[cfg(not(all(target_os = "linux", feature = "tray")))]
+pub fn subscription() -> Subscription<TrayMessage> {
+    Subscription::none()
+}
+
+#[cfg(not(all(target_os = "linux", feature = "tray")))]
+pub async fn set_running(_running: bool) {}
@@ -1,8 +1,18 @@
+use log::debug;
 use serde::{Deserialize, Serialize};
 
+use crate::client::{Client, Result as NetResult};
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct WorkspaceId(u64);
 
+#[cfg(test)]
+impl From<u64> for WorkspaceId {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
 impl std::fmt::Display for WorkspaceId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
@@ -13,4 +23,59 @@ impl std::fmt::Display for WorkspaceId {
 pub struct Workspace {
     pub id: WorkspaceId,
     pub name: String,
+    #[serde(default)]
+    pub premium: bool,
+    #[serde(default)]
+    pub business_ws: bool,
+    /// The current user may manage other members' time here.
+    #[serde(default)]
+    pub admin: bool,
+}
+
+impl Workspace {
+    /// Whether paid-only fields (billable, tasks) can be set here.
+    pub fn is_paid(&self) -> bool {
+        self.premium || self.business_ws
+    }
+}
+
+/// A member of a workspace, as listed for its admins.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceUser {
+    pub id: u64,
+    #[serde(default, alias = "name")]
+    pub fullname: String,
+    #[serde(default)]
+    pub email: String,
+}
+
+impl WorkspaceUser {
+    pub async fn load(
+        workspace_id: WorkspaceId,
+        client: &Client,
+    ) -> NetResult<Vec<Self>> {
+        debug!("Fetching users of workspace {workspace_id}...");
+        let mut res = client
+            .get(
+                [
+                    Client::BASE_URL,
+                    &format!("/api/v9/workspaces/{workspace_id}/users"),
+                ]
+                .join(""),
+            )
+            .send()
+            .await?;
+        Client::check_status(&mut res).await?;
+        res.body_json().await
+    }
+}
+
+impl std::fmt::Display for WorkspaceUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.fullname.is_empty() {
+            f.write_str(&self.email)
+        } else {
+            f.write_str(&self.fullname)
+        }
+    }
 }